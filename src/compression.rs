@@ -0,0 +1,99 @@
+// compression.rs
+// optional feature
+
+use std::io::Read;
+
+use crate::packet::PacketErr;
+
+/// Undo the content codings applied to `data`, listed in the order they were applied (as in `Content-Encoding: gzip, br`).
+///
+/// The codings are decoded in reverse order. Supported codings: `gzip` (`x-gzip`), `deflate`, `br` and `identity`.
+pub fn decode(data: &[u8], encodings: &[String]) -> Result<Vec<u8>, PacketErr> {
+    let mut acc: Vec<u8> = data.to_vec();
+    for coding in encodings.iter().rev() {
+        acc = decode_one(&acc, coding)?;
+    }
+    Ok(acc)
+}
+
+/// Decode a single content coding
+fn decode_one(data: &[u8], coding: &str) -> Result<Vec<u8>, PacketErr> {
+    let mut out: Vec<u8> = Vec::new();
+    let res = match coding.to_ascii_lowercase().as_str() {
+        "gzip" | "x-gzip" => flate2::read::GzDecoder::new(data).read_to_end(&mut out),
+        "deflate" => {
+            // `deflate` is supposed to be zlib-wrapped, but some servers send raw deflate streams
+            match flate2::read::ZlibDecoder::new(data).read_to_end(&mut out) {
+                Ok(n) => Ok(n),
+                Err(_) => {
+                    out.clear();
+                    flate2::read::DeflateDecoder::new(data).read_to_end(&mut out)
+                }
+            }
+        }
+        "br" => brotli::Decompressor::new(data, 4096).read_to_end(&mut out),
+        "identity" => {
+            out.extend_from_slice(data);
+            Ok(data.len())
+        }
+        _ => return Err(PacketErr::UnsupportedContentEncoding(coding.to_string())),
+    };
+    res.map_err(|_| PacketErr::DecompressionFailed(coding.to_string()))?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod decode_tests {
+    use super::*;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    fn brotli(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        {
+            let mut enc = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+            enc.write_all(data).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn brotli_body() {
+        let compressed = brotli(b"Hello, brotli!");
+        assert_eq!(
+            decode(&compressed, &["br".to_string()]),
+            Ok(b"Hello, brotli!".to_vec())
+        );
+    }
+
+    #[test]
+    fn chained_gzip_then_brotli() {
+        // `Content-Encoding: gzip, br` means gzip was applied first
+        let compressed = brotli(&gzip(b"twice compressed"));
+        assert_eq!(
+            decode(&compressed, &["gzip".to_string(), "br".to_string()]),
+            Ok(b"twice compressed".to_vec())
+        );
+    }
+
+    #[test]
+    fn unsupported_coding() {
+        assert_eq!(
+            decode(b"abc", &["zstd".to_string()]),
+            Err(PacketErr::UnsupportedContentEncoding("zstd".to_string()))
+        );
+    }
+
+    #[test]
+    fn corrupt_data() {
+        assert_eq!(
+            decode(b"not gzip", &["gzip".to_string()]),
+            Err(PacketErr::DecompressionFailed("gzip".to_string()))
+        );
+    }
+}
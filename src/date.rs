@@ -0,0 +1,120 @@
+// date.rs
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Parse an HTTP-date (RFC 9110 5.6.7) into seconds since the Unix epoch.
+///
+/// All three allowed formats are accepted:
+/// - IMF-fixdate: `Sun, 06 Nov 1994 08:49:37 GMT`
+/// - RFC 850: `Sunday, 06-Nov-94 08:49:37 GMT`
+/// - asctime: `Sun Nov  6 08:49:37 1994`
+///
+/// Two-digit RFC 850 years below 70 are taken to be in the 2000s. The weekday is not checked against the date.
+/// Returns `None` if the date is malformed or before the epoch.
+pub fn parse_http_date(s: &str) -> Option<u64> {
+    let parts: Vec<&str> = s.split_whitespace().collect();
+
+    let (day, month, year, time): (&str, &str, u64, &str) = match parts.as_slice() {
+        // IMF-fixdate
+        [weekday, day, month, year, time, "GMT"] if weekday.ends_with(',') => {
+            if year.len() != 4 {
+                return None;
+            }
+            (day, month, year.parse().ok()?, time)
+        }
+        // RFC 850
+        [weekday, date, time, "GMT"] if weekday.ends_with(',') => {
+            let mut date_parts = date.split('-');
+            let (day, month, year) = (date_parts.next()?, date_parts.next()?, date_parts.next()?);
+            if date_parts.next().is_some() || year.len() != 2 {
+                return None;
+            }
+            let year: u64 = year.parse().ok()?;
+            (day, month, if year < 70 { 2000 + year } else { 1900 + year }, time)
+        }
+        // asctime
+        [_weekday, month, day, time, year] => {
+            if year.len() != 4 {
+                return None;
+            }
+            (day, month, year.parse().ok()?, time)
+        }
+        _ => return None,
+    };
+
+    let day: u64 = parse_digits(day, 1..=2)?;
+    let month: u64 = MONTHS.iter().position(|m| *m == month)? as u64 + 1;
+
+    let mut time_parts = time.split(':');
+    let hour: u64 = parse_digits(time_parts.next()?, 2..=2)?;
+    let minute: u64 = parse_digits(time_parts.next()?, 2..=2)?;
+    let second: u64 = parse_digits(time_parts.next()?, 2..=2)?;
+    if time_parts.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    if year < 1970 || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    Some(days_since_epoch(year, month, day) * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Parse a string of ASCII digits whose length is within `len`
+fn parse_digits(s: &str, len: std::ops::RangeInclusive<usize>) -> Option<u64> {
+    if !len.contains(&s.len()) || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn is_leap_year(year: u64) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days from 1970-01-01 to the given date. The date must not be before the epoch.
+fn days_since_epoch(year: u64, month: u64, day: u64) -> u64 {
+    let year_days: u64 = (1970..year).map(|y| if is_leap_year(y) { 366 } else { 365 }).sum();
+    let month_days: u64 = (1..month).map(|m| days_in_month(year, m)).sum();
+    year_days + month_days + day - 1
+}
+
+#[cfg(test)]
+mod http_date_tests {
+    use super::*;
+
+    #[test]
+    fn all_formats_same_instant() {
+        let expected: Option<u64> = Some(784111777);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), expected);
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), expected);
+        assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), expected);
+    }
+
+    #[test]
+    fn epoch_and_leap_day() {
+        assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"), Some(0));
+        assert_eq!(parse_http_date("Thu, 29 Feb 2024 12:00:00 GMT"), Some(1709208000));
+        assert_eq!(parse_http_date("Monday, 01-Jan-24 00:00:00 GMT"), Some(1704067200));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(parse_http_date(""), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 UTC"), None);
+        assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 31 Nov 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 24:00:00 GMT"), None);
+        assert_eq!(parse_http_date("Thu, 01 Jan 1960 00:00:00 GMT"), None);
+        assert_eq!(parse_http_date("Sun Nov  6 08:49 1994"), None);
+    }
+}
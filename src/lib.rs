@@ -3,17 +3,34 @@ mod obj;
 /// Packet structures and builders
 pub mod packet;
 
+/// HTTP-date parsing
+pub mod date;
+
 /// Reading and collecting packets from streams. `reader` feature needed.
 #[cfg(feature = "reader")]
 pub mod reader;
 
+/// Writing packets to streams. `reader` feature needed.
+#[cfg(feature = "reader")]
+pub mod writer;
+
+/// Decoding of compressed bodies. `compression` feature needed.
+#[cfg(feature = "compression")]
+pub mod compression;
+
 pub use packet::PacketErr;
 
 pub use obj::{
     Version,
     Header,
+    split_header_line,
     StatusCode,
     StatusCodeInt,
     Body,
     Method,
+    Link,
+    Cookie,
 };
+
+/// Percent-encoding and decoding of URLs
+pub use obj::uri;
@@ -1,11 +1,129 @@
 /// A structure representing a HTTP packet body
-
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Body(pub String);
+///
+/// Bodies are compared by their bytes, so `Text("a")` equals `Bytes(vec![b'a'])`.
+///
+/// With the `serde` feature, text bodies are serialized as strings and byte bodies as arrays of numbers.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum Body {
+    /// A UTF-8 body
+    Text(String),
+    /// A body that isn't (or isn't known to be) valid UTF-8, e.g. an image or a compressed payload
+    Bytes(Vec<u8>),
+}
 
 impl Body {
-    /// Check if the body can be parsed to JSON
+    /// Create a body from raw bytes. Valid UTF-8 becomes [`Body::Text`], anything else [`Body::Bytes`].
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => Body::Text(text),
+            Err(e) => Body::Bytes(e.into_bytes()),
+        }
+    }
+
+    /// The raw bytes of the body
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Body::Text(text) => text.as_bytes(),
+            Body::Bytes(bytes) => bytes,
+        }
+    }
+
+    /// The body as text, or `None` if it is not valid UTF-8
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Body::Text(text) => Some(text),
+            Body::Bytes(bytes) => std::str::from_utf8(bytes).ok(),
+        }
+    }
+
+    /// Consume the body, returning its raw bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            Body::Text(text) => text.into_bytes(),
+            Body::Bytes(bytes) => bytes,
+        }
+    }
+
+    /// The length of the body in bytes
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    /// Whether the body has no bytes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the body can be parsed to JSON. `json` feature needed.
+    ///
+    /// ```
+    /// use httpsplitter::Body;
+    ///
+    /// assert!(Body::from(r#"{"ok": true}"#).is_valid_json());
+    /// assert!(!Body::from("{ok: true}").is_valid_json());
+    /// ```
+    #[cfg(feature = "json")]
     pub fn is_valid_json(&self) -> bool {
-        serde_json::from_str::<serde_json::Value>(self.0.as_str()).is_ok()
+        self.as_str()
+            .is_some_and(|text| serde_json::from_str::<serde_json::Value>(text).is_ok())
+    }
+}
+
+impl PartialEq for Body {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for Body {}
+
+impl From<String> for Body {
+    fn from(text: String) -> Self {
+        Body::Text(text)
+    }
+}
+
+impl From<&str> for Body {
+    fn from(text: &str) -> Self {
+        Body::Text(text.to_string())
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    fn from(bytes: Vec<u8>) -> Self {
+        Body::from_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod body_tests {
+    use super::*;
+
+    #[test]
+    fn from_bytes() {
+        assert_eq!(Body::from_bytes(b"text".to_vec()).as_str(), Some("text"));
+        assert!(matches!(Body::from_bytes(b"text".to_vec()), Body::Text(_)));
+
+        let binary = Body::from_bytes(vec![0x89, b'P', b'N', b'G', 0xff]);
+        assert!(matches!(binary, Body::Bytes(_)));
+        assert_eq!(binary.as_str(), None);
+        assert_eq!(binary.len(), 5);
+        assert_eq!(binary.into_bytes(), vec![0x89, b'P', b'N', b'G', 0xff]);
+    }
+
+    #[test]
+    fn compared_by_bytes() {
+        assert_eq!(Body::Text("abc".to_string()), Body::Bytes(b"abc".to_vec()));
+        assert_ne!(Body::Text("abc".to_string()), Body::Text("abd".to_string()));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        assert!(Body::from(r#"{"a": 1}"#).is_valid_json());
+        assert!(Body::Bytes(br#"[1, 2]"#.to_vec()).is_valid_json());
+        assert!(!Body::Bytes(vec![0xff]).is_valid_json());
     }
 }
@@ -0,0 +1,96 @@
+/// A cookie from a `Set-Cookie` header (RFC 6265), e.g. `id=a3fWa; Path=/; Max-Age=3600; Secure; HttpOnly`
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// The `Path` attribute
+    pub path: Option<String>,
+    /// The `Domain` attribute
+    pub domain: Option<String>,
+    /// The `Max-Age` attribute in seconds. Zero or negative values mean the cookie expires right away.
+    pub max_age: Option<i64>,
+    /// Whether the `Secure` attribute is set
+    pub secure: bool,
+    /// Whether the `HttpOnly` attribute is set
+    pub http_only: bool,
+}
+
+impl Cookie {
+    /// Parse a `Set-Cookie` header value. Attribute names are case-insensitive, and unknown or malformed attributes are ignored.
+    ///
+    /// `None` if there is no `name=value` pair before the first `;`, or the name is empty.
+    pub fn parse_set_cookie(value: &str) -> Option<Cookie> {
+        let mut parts = value.split(';');
+        let (name, val) = parts.next()?.split_once('=')?;
+        let name: &str = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = Cookie {
+            name: name.to_string(),
+            value: val.trim().trim_matches('"').to_string(),
+            ..Cookie::default()
+        };
+        for attr in parts {
+            let (attr_name, attr_value) = match attr.split_once('=') {
+                Some((n, v)) => (n.trim(), v.trim()),
+                None => (attr.trim(), ""),
+            };
+            match attr_name.to_ascii_lowercase().as_str() {
+                "path" => cookie.path = Some(attr_value.to_string()),
+                "domain" => cookie.domain = Some(attr_value.trim_start_matches('.').to_string()),
+                "max-age" => cookie.max_age = attr_value.parse().ok().or(cookie.max_age),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                _ => {}
+            }
+        }
+        Some(cookie)
+    }
+
+    /// Parse the `name=value` pairs of a `Cookie` header value, e.g. `a=1; b=2`. Pairs without `=` or with an empty name are skipped.
+    pub fn parse_cookie_header(value: &str) -> Vec<(String, String)> {
+        value
+            .split(';')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, val)| (name.trim(), val.trim().trim_matches('"')))
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, val)| (name.to_string(), val.to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod cookie_tests {
+    use super::*;
+
+    #[test]
+    fn set_cookie_attributes() {
+        let cookie = Cookie::parse_set_cookie("id=a3fWa; Path=/docs; domain=.example.com; Max-Age=3600; Secure; HttpOnly; SameSite=Lax").unwrap();
+        assert_eq!(cookie, Cookie {
+            name: "id".into(),
+            value: "a3fWa".into(),
+            path: Some("/docs".into()),
+            domain: Some("example.com".into()),
+            max_age: Some(3600),
+            secure: true,
+            http_only: true,
+        });
+    }
+
+    #[test]
+    fn set_cookie_malformed() {
+        assert_eq!(Cookie::parse_set_cookie("no-equals-sign; Secure"), None);
+        assert_eq!(Cookie::parse_set_cookie("=value"), None);
+        assert_eq!(Cookie::parse_set_cookie("a=b; Max-Age=soon").unwrap().max_age, None);
+    }
+
+    #[test]
+    fn cookie_header() {
+        assert_eq!(
+            Cookie::parse_cookie_header("a=1; b=\"two\";c=; broken"),
+            vec![("a".into(), "1".into()), ("b".into(), "two".into()), ("c".into(), "".into())]
+        );
+    }
+}
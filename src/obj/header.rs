@@ -8,11 +8,45 @@ use crate::packet::PacketErr;
 ///     dolor sit amet
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     pub key: String,
     pub value: String,
 }
 
+impl Header {
+    /// Create a header without validating it
+    pub fn new<K: Into<String>, V: Into<String>>(key: K, value: V) -> Self {
+        Header { key: key.into(), value: value.into() }
+    }
+
+    /// Create a header, failing with [`PacketErr::MalformedHeader`] if the key is not a token (see [`Header::try_from`])
+    pub fn try_new<K: Into<String>, V: Into<String>>(key: K, value: V) -> Result<Self, PacketErr> {
+        let header: Header = Header::new(key, value);
+        if !is_token(&header.key) {
+            return Err(PacketErr::MalformedHeader(header.to_string()));
+        }
+        Ok(header)
+    }
+}
+
+/// Whether `s` is a non-empty RFC 7230 token: letters, digits and ``!#$%&'*+-.^_`|~``
+pub(crate) fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// Split a header line into its key and value without allocating, e.g. `Key:  Value ` -> `("Key", "Value")`.
+///
+/// The value is trimmed. Follows the same rules as [`Header::try_from`], failing with [`PacketErr::MalformedHeader`]
+/// if there is no `:` or the key is not a token. Whitespace around the key is not allowed (RFC 7230 3.2.4),
+/// so `Key : Value` is rejected.
+pub fn split_header_line(line: &str) -> Result<(&str, &str), PacketErr> {
+    match line.split_once(':') {
+        Some((key, value)) if is_token(key) => Ok((key, value.trim())),
+        _ => Err(PacketErr::MalformedHeader(line.to_string())),
+    }
+}
+
 impl TryFrom<&str> for Header {
     /// Assume the following header format:
     /// ```text
@@ -28,20 +62,17 @@ impl TryFrom<&str> for Header {
     ///     ipsum
     ///         dolor
     /// ```
+    /// Whitespace around the value is trimmed, so `Key:  Value ` yields the value `Value`.
+    ///
+    /// The key must be a token (letters, digits and ``!#$%&'*+-.^_`|~``), so empty keys and keys with spaces or control characters are rejected.
+    /// This includes whitespace between the key and the `:`, or before the key.
     type Error = PacketErr;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut parts: Option<(&str, &str)> = value.split_once(":");
-
-        if parts.is_none() { 
-            return Err(PacketErr::MalformedHeader(value.to_string()));
-        }
-
-        let parts: (&str, &str) = parts.unwrap();
-
-        return Ok(Self {
-            key: parts.0.into(),
-            value: parts.1.into()
-        });
+        let (key, value): (&str, &str) = split_header_line(value)?;
+        Ok(Self {
+            key: key.into(),
+            value: value.into()
+        })
     }
 }
 
@@ -51,3 +82,89 @@ impl std::fmt::Display for Header {
         write!(f, "{}: {}", self.key, self.value)
     }
 }
+
+#[cfg(test)]
+mod header_tests {
+    use super::*;
+
+    #[test]
+    fn trims_value() {
+        let h = Header::try_from("Host: example.com").unwrap();
+        assert_eq!(h.key, "Host");
+        assert_eq!(h.value, "example.com");
+    }
+
+    #[test]
+    fn keeps_inner_whitespace() {
+        let h = Header::try_from("Key:  multiple   spaces").unwrap();
+        assert_eq!(h.value, "multiple   spaces");
+        assert_eq!(h.to_string(), "Key: multiple   spaces");
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert_eq!(
+            Header::try_from("Bad Key: v"),
+            Err(PacketErr::MalformedHeader("Bad Key: v".to_string()))
+        );
+        assert!(Header::try_from(": no key").is_err());
+        assert!(Header::try_from("Bad\u{1}Key: v").is_err());
+        assert!(Header::try_from("Bad(Key): v").is_err());
+    }
+
+    #[test]
+    fn whitespace_around_key() {
+        assert_eq!(
+            Header::try_from("Key : v"),
+            Err(PacketErr::MalformedHeader("Key : v".to_string()))
+        );
+        assert!(Header::try_from("Content-Length\t: 5").is_err());
+        assert!(Header::try_from(" Host: x").is_err());
+    }
+
+    #[test]
+    fn valid_token_key() {
+        let h = Header::try_from("X-Custom_Header.v2!#$%&'*+^`|~: v").unwrap();
+        assert_eq!(h.key, "X-Custom_Header.v2!#$%&'*+^`|~");
+        assert_eq!(h.value, "v");
+    }
+
+    #[test]
+    fn new() {
+        let h = Header::new("Key", String::from("Value"));
+        assert_eq!(h, Header { key: "Key".to_string(), value: "Value".to_string() });
+        assert_eq!(Header::new("Bad Key", "v").key, "Bad Key");
+    }
+
+    #[test]
+    fn try_new() {
+        assert_eq!(Header::try_new("Content-Type", "text/plain"), Ok(Header::new("Content-Type", "text/plain")));
+        assert_eq!(
+            Header::try_new("Bad Key", "v"),
+            Err(PacketErr::MalformedHeader("Bad Key: v".to_string()))
+        );
+        assert!(Header::try_new("", "v").is_err());
+    }
+
+    #[test]
+    fn split_line() {
+        assert_eq!(split_header_line("Content-Type:  text/html; charset=utf-8 "), Ok(("Content-Type", "text/html; charset=utf-8")));
+        assert_eq!(split_header_line("Empty:"), Ok(("Empty", "")));
+        assert_eq!(split_header_line("Host: example.com:8080"), Ok(("Host", "example.com:8080")));
+    }
+
+    #[test]
+    fn split_line_malformed() {
+        assert_eq!(split_header_line("no colon"), Err(PacketErr::MalformedHeader("no colon".to_string())));
+        assert_eq!(split_header_line("Bad Key: v"), Err(PacketErr::MalformedHeader("Bad Key: v".to_string())));
+        assert_eq!(split_header_line("Key : v"), Err(PacketErr::MalformedHeader("Key : v".to_string())));
+    }
+
+    #[test]
+    fn no_colon() {
+        assert_eq!(
+            Header::try_from("NoColon"),
+            Err(PacketErr::MalformedHeader("NoColon".to_string()))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+/// A single link from a `Link` header (RFC 8288), e.g. `<https://example.com/?page=2>; rel="next"`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Link {
+    /// The target URI, without the angle brackets
+    pub uri: String,
+    /// The link parameters in order of appearance. Names are lowercased, quoted values are unquoted.
+    pub params: Vec<(String, String)>,
+}
+
+impl Link {
+    /// Value of the first parameter named `name` (case-insensitive), e.g. `link.param("rel")`
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Parse every link in a `Link` header value.
+    ///
+    /// Commas inside the URI or quoted parameter values do not split links.
+    /// Parsing stops at the first malformed link, returning the ones parsed before it.
+    pub fn parse_header(value: &str) -> Vec<Link> {
+        let mut links: Vec<Link> = Vec::new();
+        let mut chars = value.chars().peekable();
+
+        loop {
+            // skip the separators between links
+            while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+            if chars.next() != Some('<') {
+                break;
+            }
+
+            let uri: String = chars.by_ref().take_while(|c| *c != '>').collect();
+            let mut params: Vec<(String, String)> = Vec::new();
+
+            // parameters: `; name=value` or `; name="quoted value"` or `; name`
+            loop {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.next_if_eq(&';').is_none() {
+                    break;
+                }
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| !matches!(c, '=' | ';' | ',') && !c.is_whitespace()) {
+                    name.push(c.to_ascii_lowercase());
+                }
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+                let mut val = String::new();
+                if chars.next_if_eq(&'=').is_some() {
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    if chars.next_if_eq(&'"').is_some() {
+                        while let Some(c) = chars.next() {
+                            match c {
+                                '"' => break,
+                                '\\' => {
+                                    if let Some(escaped) = chars.next() {
+                                        val.push(escaped);
+                                    }
+                                }
+                                _ => val.push(c),
+                            }
+                        }
+                    } else {
+                        while let Some(c) = chars.next_if(|c| !matches!(c, ';' | ',')) {
+                            val.push(c);
+                        }
+                        val.truncate(val.trim_end().len());
+                    }
+                }
+
+                if !name.is_empty() {
+                    params.push((name, val));
+                }
+            }
+
+            links.push(Link { uri, params });
+        }
+
+        links
+    }
+}
+
+#[cfg(test)]
+mod link_tests {
+    use super::*;
+
+    #[test]
+    fn two_links() {
+        let links = Link::parse_header(r#"<https://x>; rel="next", <https://y>; rel="prev""#);
+        assert_eq!(
+            links,
+            vec![
+                Link { uri: "https://x".into(), params: vec![("rel".into(), "next".into())] },
+                Link { uri: "https://y".into(), params: vec![("rel".into(), "prev".into())] },
+            ]
+        );
+        assert_eq!(links[1].param("REL"), Some("prev"));
+    }
+
+    #[test]
+    fn commas_in_quotes_and_uri() {
+        let links = Link::parse_header(r#"</a,b>; title="one, two"; rel=last"#);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].uri, "/a,b");
+        assert_eq!(links[0].param("title"), Some("one, two"));
+        assert_eq!(links[0].param("rel"), Some("last"));
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(Link::parse_header("https://no-brackets").is_empty());
+        assert!(Link::parse_header("").is_empty());
+    }
+}
@@ -1,5 +1,11 @@
+use crate::packet::PacketErr;
+use super::header::is_token;
+
 /// Taken from <https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Methods>
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Methods not listed there, e.g. WebDAV's `PROPFIND`, are kept as [`Method::Extension`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Method {
     Get,
     Head,
@@ -10,27 +16,98 @@ pub enum Method {
     Post,
     Patch,
     Connect,
+    /// Any other method, e.g. `PROPFIND`. The name is an all-uppercase token.
+    Extension(String),
 }
 
 
 impl Method {
+    /// Same as [`str::parse`], but returns an `Option`. Kept for backwards compatibility.
     pub fn try_from(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+
+    /// Whether this is `GET`
+    pub fn is_get(&self) -> bool {
+        matches!(self, Self::Get)
+    }
+
+    /// Whether this is `HEAD`
+    pub fn is_head(&self) -> bool {
+        matches!(self, Self::Head)
+    }
+
+    /// Whether this is `OPTIONS`
+    pub fn is_options(&self) -> bool {
+        matches!(self, Self::Options)
+    }
+
+    /// Whether this is `TRACE`
+    pub fn is_trace(&self) -> bool {
+        matches!(self, Self::Trace)
+    }
+
+    /// Whether this is `PUT`
+    pub fn is_put(&self) -> bool {
+        matches!(self, Self::Put)
+    }
+
+    /// Whether this is `DELETE`
+    pub fn is_delete(&self) -> bool {
+        matches!(self, Self::Delete)
+    }
+
+    /// Whether this is `POST`
+    pub fn is_post(&self) -> bool {
+        matches!(self, Self::Post)
+    }
+
+    /// Whether this is `PATCH`
+    pub fn is_patch(&self) -> bool {
+        matches!(self, Self::Patch)
+    }
+
+    /// Whether this is `CONNECT`
+    pub fn is_connect(&self) -> bool {
+        matches!(self, Self::Connect)
+    }
+
+    /// Whether the method is safe (RFC 7231 4.2.1), i.e. read-only: `GET`, `HEAD`, `OPTIONS` and `TRACE`
+    pub fn is_safe(&self) -> bool {
+        matches!(self, Self::Get | Self::Head | Self::Options | Self::Trace)
+    }
+
+    /// Whether the method is idempotent (RFC 7231 4.2.2): the safe methods, `PUT` and `DELETE`
+    pub fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(self, Self::Put | Self::Delete)
+    }
+}
+
+impl std::str::FromStr for Method {
+    type Err = PacketErr;
+
+    /// Surrounding whitespace is ignored. Unknown methods become [`Method::Extension`] if they are tokens without lowercase letters,
+    /// and fail with [`PacketErr::InvalidMethod`] otherwise.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.trim() {
-            "GET" => Some(Self::Get),
-            "HEAD" => Some(Self::Head),
-            "OPTIONS" => Some(Self::Options),
-            "TRACE" => Some(Self::Trace),
-            "PUT" => Some(Self::Put),
-            "DELETE" => Some(Self::Delete),
-            "POST" => Some(Self::Post),
-            "PATCH" => Some(Self::Patch),
-            "CONNECT" => Some(Self::Connect),
-            _ => None
+            "GET" => Ok(Self::Get),
+            "HEAD" => Ok(Self::Head),
+            "OPTIONS" => Ok(Self::Options),
+            "TRACE" => Ok(Self::Trace),
+            "PUT" => Ok(Self::Put),
+            "DELETE" => Ok(Self::Delete),
+            "POST" => Ok(Self::Post),
+            "PATCH" => Ok(Self::Patch),
+            "CONNECT" => Ok(Self::Connect),
+            other if is_token(other) && !other.bytes().any(|b| b.is_ascii_lowercase()) => Ok(Self::Extension(other.to_string())),
+            _ => Err(PacketErr::InvalidMethod)
         }
     }
+}
 
-    pub fn to_string(&self) -> String {
-        let slice = match &self {
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
             Self::Get => "GET",
             Self::Head => "HEAD",
             Self::Options => "OPTIONS",
@@ -39,15 +116,74 @@ impl Method {
             Self::Delete => "DELETE",
             Self::Post => "POST",
             Self::Patch => "PATCH",
-            Self::Connect => "CONNECT"
+            Self::Connect => "CONNECT",
+            Self::Extension(name) => name,
         };
-        slice.to_string()
-    }
-}
-
-impl std::fmt::Display for Method {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let repr = self.to_string();
         write!(f, "{}", repr)
     }   
 }
+
+#[cfg(test)]
+mod method_parse_tests {
+    use super::*;
+
+    #[test]
+    fn parse_post() {
+        assert_eq!("POST".parse::<Method>(), Ok(Method::Post));
+    }
+
+    #[test]
+    fn parse_unknown() {
+        assert_eq!("get".parse::<Method>(), Err(PacketErr::InvalidMethod));
+        assert_eq!("Propfind".parse::<Method>(), Err(PacketErr::InvalidMethod));
+        assert_eq!("PROP FIND".parse::<Method>(), Err(PacketErr::InvalidMethod));
+        assert_eq!("PROP(FIND)".parse::<Method>(), Err(PacketErr::InvalidMethod));
+        assert_eq!("".parse::<Method>(), Err(PacketErr::InvalidMethod));
+    }
+
+    #[test]
+    fn parse_extension() {
+        let method: Method = "PROPFIND".parse().unwrap();
+        assert_eq!(method, Method::Extension("PROPFIND".to_string()));
+        assert_eq!(method.to_string(), "PROPFIND");
+        assert_eq!(Method::try_from("M-SEARCH"), Some(Method::Extension("M-SEARCH".to_string())));
+        assert!(!method.is_safe());
+    }
+
+    #[test]
+    fn predicates() {
+        type Predicate = fn(&Method) -> bool;
+        let predicates: [(Method, Predicate); 9] = [
+            (Method::Get, Method::is_get),
+            (Method::Head, Method::is_head),
+            (Method::Options, Method::is_options),
+            (Method::Trace, Method::is_trace),
+            (Method::Put, Method::is_put),
+            (Method::Delete, Method::is_delete),
+            (Method::Post, Method::is_post),
+            (Method::Patch, Method::is_patch),
+            (Method::Connect, Method::is_connect),
+        ];
+        for (method, _) in &predicates {
+            for (other, predicate) in &predicates {
+                assert_eq!(predicate(method), method == other, "{method} vs {other}");
+            }
+        }
+    }
+
+    #[test]
+    fn safe_and_idempotent() {
+        assert!(Method::Get.is_safe() && Method::Get.is_idempotent());
+        assert!(!Method::Post.is_safe() && !Method::Post.is_idempotent());
+        assert!(!Method::Put.is_safe() && Method::Put.is_idempotent());
+        assert!(!Method::Patch.is_safe() && !Method::Patch.is_idempotent());
+        assert!(!Method::Delete.is_safe() && Method::Delete.is_idempotent());
+        assert!(!Method::Connect.is_safe() && !Method::Connect.is_idempotent());
+    }
+
+    #[test]
+    fn try_from_delegates() {
+        assert_eq!(Method::try_from(" DELETE "), Some(Method::Delete));
+        assert_eq!(Method::try_from("foo"), None);
+    }
+}
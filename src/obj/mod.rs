@@ -3,10 +3,32 @@ pub mod method;
 pub mod header;
 pub mod body;
 pub mod status;
+pub mod link;
+pub mod cookie;
+pub mod uri;
 
 pub use version::Version;
 pub use method::Method;
-pub use header::Header;
+pub use header::{Header, split_header_line};
 pub use body::Body;
 pub use status::{StatusCode, StatusCodeInt};
+pub use link::Link;
+pub use cookie::Cookie;
 
+
+#[cfg(test)]
+mod obj_tests {
+    use super::*;
+
+    #[test]
+    fn construct_each_type() {
+        assert_eq!(Version::V1_1.to_string(), "HTTP/1.1");
+        assert_eq!("GET".parse::<Method>(), Ok(Method::Get));
+        assert_eq!(Header::new("Host", "example.com").to_string(), "Host: example.com");
+        assert_eq!(Body::from("hello").len(), 5);
+        assert_eq!(StatusCode::try_from_int(404), Some(StatusCode::NotFound));
+        assert_eq!(StatusCode::Ok.as_int(), 200 as StatusCodeInt);
+        assert_eq!(Link::parse_header("</next>; rel=next")[0].uri, "/next");
+        assert_eq!(Cookie::parse_set_cookie("a=1").map(|c| c.value), Some("1".to_string()));
+    }
+}
@@ -1,7 +1,8 @@
-use crate::packet::PacketErr;
+use crate::packet::{PacketErr, ResponsePacket};
+use crate::obj::{Body, Header, Version};
 
-/// The status code returned with responses
-pub type StatusCodeInt = usize;
+/// The status code returned with responses. Status codes are three digits, so they always fit.
+pub type StatusCodeInt = u16;
 
 /// From <https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status>
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -142,6 +143,12 @@ pub enum StatusCode {
     NotExtended,
     /// 511
     NetworkAuthenticationRequired,
+
+    /// Any other code from `100` to `599`, e.g. a vendor-specific `599`, with its reason phrase.
+    ///
+    /// Known codes always use their own variant. A nonstandard phrase for them, e.g. `200 Okay Dokay`,
+    /// is kept in [`ResponsePacket::reason`](crate::packet::ResponsePacket::reason).
+    Custom(u16, String),
 }
 
 
@@ -215,9 +222,13 @@ impl StatusCode {
             StatusCode::LoopDetected => 508,
             StatusCode::NotExtended => 510,
             StatusCode::NetworkAuthenticationRequired => 511,
+
+            StatusCode::Custom(code, _) => *code,
         }
     }
 
+    /// Codes from `100` to `599` without a variant of their own become [`StatusCode::Custom`] with an empty reason phrase.
+    /// `None` for codes outside that range.
     pub fn try_from_int(int: StatusCodeInt) -> Option<Self> {
         match int {
             100 => Some(Self::Continue),
@@ -288,6 +299,8 @@ impl StatusCode {
             510 => Some(Self::NotExtended),
             511 => Some(Self::NetworkAuthenticationRequired),
 
+            // unknown codes are kept, without a reason phrase
+            _ if (100..=599).contains(&int) => Some(Self::Custom(int, String::new())),
             _ => None,
         }
     }
@@ -361,6 +374,8 @@ impl StatusCode {
             StatusCode::LoopDetected => "Loop Detected",
             StatusCode::NotExtended => "Not Extended",
             StatusCode::NetworkAuthenticationRequired => "Network Authentication Required",
+
+            StatusCode::Custom(_, reason) => reason.as_str(),
         }
         .to_string()
     }
@@ -373,34 +388,136 @@ impl StatusCode {
         )
     }
 
+    /// `1xx`
+    pub fn is_informational(&self) -> bool {
+        (100..200).contains(&self.as_int())
+    }
+
+    /// `2xx`
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.as_int())
+    }
+
+    /// `3xx`
+    pub fn is_redirection(&self) -> bool {
+        (300..400).contains(&self.as_int())
+    }
+
+    /// `4xx`
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.as_int())
+    }
+
+    /// `5xx`
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.as_int())
+    }
+
+    /// Whether a response with this status may have a body. Bodies are not allowed for `1xx`, `204` and `304`.
+    pub(crate) fn allows_body(&self) -> bool {
+        let code: StatusCodeInt = self.as_int();
+        !(self.is_informational() || code == 204 || code == 304)
+    }
+
+    /// A valid response with just the status line, and `Content-Length: 0` with an empty body if the status allows a body.
+    ///
+    /// Handy for stubs and health checks.
+    pub fn minimal_response(&self, version: Version) -> ResponsePacket {
+        let (headers, body) = if self.allows_body() {
+            (
+                Some(vec![Header { key: "Content-Length".into(), value: "0".into() }]),
+                Some(Body::Text(String::new())),
+            )
+        } else {
+            (None, None)
+        };
+
+        ResponsePacket {
+            version,
+            status: Some(self.clone()),
+            reason: None,
+            headers,
+            body,
+        }
+    }
+
     /// Try to extract the status code from the first line.
     /// Only one line expected.
+    ///
+    /// The status is determined by the numeric code alone, the reason phrase only matters for [`StatusCode::Custom`].
     pub fn try_from_first_res_line(s: &str) -> Result<Self, PacketErr> {
         // Expected format: VERSION CODE CODE_DESC
-        // E.g. `HTTP/1.0 200 OK`
+        // E.g. `HTTP/1.0 200 OK` or `HTTP/1.0 204 No Content`
         let parts: Vec<&str> = s.split_whitespace().collect();
-        if parts.len() != 3 {
+        if parts.len() < 3 {
+            return Err(PacketErr::InvalidStatusLine);
+        }
+        // The description may consist of several words
+        let desc_str: String = parts[2..].join(" ");
+
+        // The code must be exactly three ASCII digits, e.g. `20` or `2000` are rejected
+        let code_str: &str = parts[1];
+        if code_str.len() != 3 || !code_str.bytes().all(|b| b.is_ascii_digit()) {
             return Err(PacketErr::InvalidStatusLine);
         }
 
-        let status_code: StatusCodeInt = (parts[1].parse::<usize>()).map_err(|_e| PacketErr::InvalidStatusLine)?;
+        let status_code: StatusCodeInt = (code_str.parse::<StatusCodeInt>()).map_err(|_e| PacketErr::InvalidStatusLine)?;
  
-        if let Some(code_enum) = Self::try_from_int(status_code) {
-            let desc = code_enum.description();
-
-            // check if the desc matches
-            if desc != parts[2] {
-                return Err(PacketErr::InvalidStatusLine);
-            }
-            else {
-                return Ok(code_enum);
-            }
+        // Only the code decides the status. The reason phrase of a known code is ignored (e.g. `200 Okay` is `200 OK`),
+        // and kept for other codes.
+        match Self::try_from_int(status_code) {
+            Some(Self::Custom(code, _)) => Ok(Self::Custom(code, desc_str)),
+            Some(code_enum) => Ok(code_enum),
+            // The code number is outside of the valid range
+            None => Err(PacketErr::InvalidStatusLine),
         }
-        else {
-            // The code number does not correspond to anything
-            return Err(PacketErr::InvalidStatusLine);
+    }
+}
+
+/// The serialized form of a [`StatusCode`]: the numeric code, or the code and reason phrase of a [`StatusCode::Custom`]
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum StatusCodeRepr<'a> {
+    Code(StatusCodeInt),
+    Custom { code: StatusCodeInt, reason: std::borrow::Cow<'a, str> },
+}
+
+/// Serialized as the numeric code, e.g. `404`.
+/// [`StatusCode::Custom`] keeps its reason phrase, e.g. `{"code":599,"reason":"Network Timeout"}`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for StatusCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            StatusCode::Custom(code, reason) => StatusCodeRepr::Custom { code: *code, reason: reason.into() },
+            known => StatusCodeRepr::Code(known.as_int()),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for StatusCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (int, reason): (StatusCodeInt, String) = match StatusCodeRepr::deserialize(deserializer)? {
+            StatusCodeRepr::Code(code) => (code, String::new()),
+            StatusCodeRepr::Custom { code, reason } => (code, reason.into_owned()),
+        };
+        // Same as when parsing a status line, the reason phrase only matters for custom codes
+        match StatusCode::try_from_int(int) {
+            Some(StatusCode::Custom(code, _)) => Ok(StatusCode::Custom(code, reason)),
+            Some(known) => Ok(known),
+            None => Err(serde::de::Error::custom(format!("unknown status code: {int}"))),
         }
+    }
+}
 
+impl TryFrom<u16> for StatusCode {
+    type Error = PacketErr;
+
+    /// Same as [`StatusCode::try_from_int`], but fails with [`PacketErr::InvalidStatusCode`] for codes outside of `100` to `599`
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        Self::try_from_int(code).ok_or(PacketErr::InvalidStatusCode(code))
     }
 }
 
@@ -423,6 +540,21 @@ mod status_code_tests {
         );
     }
 
+    #[test]
+    fn as_int_u16() {
+        assert_eq!(StatusCode::Ok.as_int(), 200u16);
+        assert_eq!(StatusCode::NetworkAuthenticationRequired.as_int(), 511u16);
+        assert_eq!(StatusCode::try_from_int(404u16), Some(StatusCode::NotFound));
+    }
+
+    #[test]
+    fn try_from_u16() {
+        assert_eq!(StatusCode::try_from(200u16), Ok(StatusCode::Ok));
+        assert_eq!(StatusCode::try_from(599u16), Ok(StatusCode::Custom(599, String::new())));
+        assert_eq!(StatusCode::try_from(999u16), Err(PacketErr::InvalidStatusCode(999)));
+        assert_eq!(StatusCode::try_from(99u16), Err(PacketErr::InvalidStatusCode(99)));
+    }
+
     #[test]
     fn code_desc_418() {
         assert_eq!(
@@ -431,3 +563,154 @@ mod status_code_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod class_tests {
+    use super::*;
+
+    /// (informational, success, redirection, client error, server error)
+    fn classes(code: &StatusCode) -> (bool, bool, bool, bool, bool) {
+        (
+            code.is_informational(),
+            code.is_success(),
+            code.is_redirection(),
+            code.is_client_error(),
+            code.is_server_error(),
+        )
+    }
+
+    #[test]
+    fn informational() {
+        assert_eq!(classes(&StatusCode::Continue), (true, false, false, false, false));
+        assert_eq!(classes(&StatusCode::EarlyHints), (true, false, false, false, false));
+    }
+
+    #[test]
+    fn success() {
+        assert_eq!(classes(&StatusCode::Ok), (false, true, false, false, false));
+        assert_eq!(classes(&StatusCode::PartialContent), (false, true, false, false, false));
+        assert_eq!(classes(&StatusCode::IMUsed), (false, true, false, false, false));
+    }
+
+    #[test]
+    fn redirection() {
+        assert_eq!(classes(&StatusCode::MultipleChoices), (false, false, true, false, false));
+        assert_eq!(classes(&StatusCode::PermanentRedirect), (false, false, true, false, false));
+    }
+
+    #[test]
+    fn client_error() {
+        assert_eq!(classes(&StatusCode::BadRequest), (false, false, false, true, false));
+        assert_eq!(classes(&StatusCode::UnavailableForLegalReasons), (false, false, false, true, false));
+    }
+
+    #[test]
+    fn server_error() {
+        assert_eq!(classes(&StatusCode::InternalServerError), (false, false, false, false, true));
+        assert_eq!(classes(&StatusCode::NetworkAuthenticationRequired), (false, false, false, false, true));
+    }
+}
+
+#[cfg(test)]
+mod minimal_response_tests {
+    use super::*;
+
+    #[test]
+    fn ok_1_1() {
+        let res = StatusCode::Ok.minimal_response(Version::V1_1);
+        assert_eq!(res.status, Some(StatusCode::Ok));
+        assert_eq!(
+            res.try_to_string(),
+            Ok("HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string())
+        );
+    }
+
+    #[test]
+    fn no_content_has_no_length() {
+        let res = StatusCode::NoContent.minimal_response(Version::V1_0);
+        assert_eq!(res.headers, None);
+        assert_eq!(res.body, None);
+        assert_eq!(
+            res.try_to_string(),
+            Ok("HTTP/1.0 204 No Content\r\n\r\n".to_string())
+        );
+    }
+}
+
+#[cfg(test)]
+mod status_line_tests {
+    use super::*;
+
+    #[test]
+    fn two_digit_code() {
+        assert_eq!(
+            Err(PacketErr::InvalidStatusLine),
+            StatusCode::try_from_first_res_line("HTTP/1.1 20 OK")
+        );
+    }
+
+    #[test]
+    fn four_digit_code() {
+        assert_eq!(
+            Err(PacketErr::InvalidStatusLine),
+            StatusCode::try_from_first_res_line("HTTP/1.1 2000 X")
+        );
+    }
+
+    #[test]
+    fn non_digit_code() {
+        assert_eq!(
+            Err(PacketErr::InvalidStatusLine),
+            StatusCode::try_from_first_res_line("HTTP/1.1 +20 OK")
+        );
+    }
+
+    #[test]
+    fn multi_word_description() {
+        assert_eq!(
+            Ok(StatusCode::NoContent),
+            StatusCode::try_from_first_res_line("HTTP/1.1 204 No Content")
+        );
+    }
+
+    #[test]
+    fn three_digit_code() {
+        assert_eq!(
+            Ok(StatusCode::Ok),
+            StatusCode::try_from_first_res_line("HTTP/1.1 200 OK")
+        );
+    }
+
+    #[test]
+    fn reason_phrase_ignored() {
+        assert_eq!(
+            Ok(StatusCode::NotFound),
+            StatusCode::try_from_first_res_line("HTTP/1.1 404 Not Found")
+        );
+        assert_eq!(
+            Ok(StatusCode::Ok),
+            StatusCode::try_from_first_res_line("HTTP/1.1 200 Okay")
+        );
+        assert_eq!(
+            Ok(StatusCode::NotFound),
+            StatusCode::try_from_first_res_line("HTTP/1.1 404 Nothing to see here")
+        );
+    }
+
+    #[test]
+    fn custom_code() {
+        let code = StatusCode::try_from_first_res_line("HTTP/1.1 599 Network Timeout").unwrap();
+        assert_eq!(code, StatusCode::Custom(599, "Network Timeout".to_string()));
+        assert_eq!(code.as_int(), 599);
+        assert_eq!(code.description(), "Network Timeout");
+        assert!(code.is_server_error());
+        assert_eq!(code.to_string(), "599 Network Timeout");
+
+        assert_eq!(StatusCode::try_from_int(299), Some(StatusCode::Custom(299, String::new())));
+        assert_eq!(
+            StatusCode::try_from_first_res_line("HTTP/1.1 999 Too Far"),
+            Err(PacketErr::InvalidStatusLine)
+        );
+        assert_eq!(StatusCode::try_from_int(99), None);
+    }
+}
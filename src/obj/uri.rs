@@ -0,0 +1,94 @@
+use crate::packet::PacketErr;
+
+/// Percent-encode `s` for use in the path or query of a request target (RFC 3986).
+///
+/// Unreserved characters, sub-delimiters and `:`, `@`, `/`, `?` are kept. Everything else, including `%`,
+/// is encoded byte by byte as `%XX` with uppercase hex digits, e.g. `/a b/ü` becomes `/a%20b/%C3%BC`.
+pub fn percent_encode(s: &str) -> String {
+    encode_except(s, b"-._~!$&'()*+,;=:@/?")
+}
+
+/// Percent-encode `s` for use as a single query key or value, or a single path segment.
+///
+/// Only unreserved characters (letters, digits and `-._~`) are kept, so delimiters such as `&`, `=`, `+` and `/` are encoded too,
+/// e.g. `a&b=c` becomes `a%26b%3Dc`.
+pub fn percent_encode_component(s: &str) -> String {
+    encode_except(s, b"-._~")
+}
+
+/// Percent-encode every byte of `s` except ASCII letters, digits and `keep`
+fn encode_except(s: &str, keep: &[u8]) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || keep.contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Decode the `%XX` escapes in `s` (RFC 3986). `+` is left as it is.
+///
+/// Fails with [`PacketErr::InvalidPercentEncoding`] if a `%` is not followed by two hex digits,
+/// or if the decoded bytes are not valid UTF-8.
+pub fn percent_decode(s: &str) -> Result<String, PacketErr> {
+    let bytes: &[u8] = s.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i: usize = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte: u8 = bytes
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok())
+                .ok_or_else(|| {
+                    let escape: String = s[i..].chars().take(3).collect();
+                    PacketErr::InvalidPercentEncoding(format!("invalid escape {escape:?} at byte {i}"))
+                })?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded)
+        .map_err(|_| PacketErr::InvalidPercentEncoding(format!("{s:?} does not decode to valid UTF-8")))
+}
+
+#[cfg(test)]
+mod uri_tests {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        assert_eq!(percent_encode("/a b/\u{fc}"), "/a%20b/%C3%BC");
+        assert_eq!(percent_encode("/search?q=a+b&x=1"), "/search?q=a+b&x=1");
+        assert_eq!(percent_encode("100%"), "100%25");
+    }
+
+    #[test]
+    fn encode_component() {
+        assert_eq!(percent_encode_component("a&b=c+d/e f"), "a%26b%3Dc%2Bd%2Fe%20f");
+        assert_eq!(percent_encode_component("Az09-._~"), "Az09-._~");
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(percent_decode("/a%20b/%C3%BC"), Ok("/a b/\u{fc}".to_string()));
+        assert_eq!(percent_decode(&percent_encode("\u{1f980} \"x\"")), Ok("\u{1f980} \"x\"".to_string()));
+        assert_eq!(percent_decode("a+b"), Ok("a+b".to_string()));
+    }
+
+    #[test]
+    fn decode_malformed() {
+        assert_eq!(
+            percent_decode("/x%ZZ"),
+            Err(PacketErr::InvalidPercentEncoding("invalid escape \"%ZZ\" at byte 2".to_string()))
+        );
+        assert!(percent_decode("100%").is_err());
+        assert!(percent_decode("%FF").is_err());
+    }
+}
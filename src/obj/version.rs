@@ -1,7 +1,9 @@
 use crate::packet::PacketErr;
 
 /// Supported HTTP versions
-#[derive(Clone, Debug, Eq, PartialEq, Copy)]
+///
+/// Versions are ordered from oldest to newest, so `version >= Version::V1_1` checks for HTTP/1.1 or later.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Copy)]
 pub enum Version {
     /// Unlike HTTP versions 1.0 and 1.1, the version 0.9 is not mentioned in the first line of the packet.
     /// 
@@ -11,7 +13,10 @@ pub enum Version {
     /// The only indication of the version considered by this crate is in the first line of the packet.
     V0_9,
     V1_0,
-    V1_1
+    V1_1,
+    /// Only the version string is supported, e.g. for replaying HTTP/2 prior-knowledge request lines.
+    /// Packets are still read and written in the textual HTTP/1.x format.
+    V2_0,
 }
 
 impl std::fmt::Display for Version {
@@ -20,26 +25,65 @@ impl std::fmt::Display for Version {
             Version::V0_9 => "",
             Version::V1_0 => "HTTP/1.0",
             Version::V1_1 => "HTTP/1.1",
+            Version::V2_0 => "HTTP/2.0",
         };
         write!(f, "{}", version_str)
     }
 }
 
 
+/// Serialized as the version string, e.g. `"HTTP/1.1"`. HTTP/0.9 has none on the wire, so `"HTTP/0.9"` is used.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Version {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Version::V0_9 => serializer.serialize_str("HTTP/0.9"),
+            v => serializer.collect_str(v),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Version {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s: String = serde::Deserialize::deserialize(deserializer)?;
+        match s.as_str() {
+            "HTTP/0.9" | "" => Ok(Version::V0_9),
+            "HTTP/1.0" => Ok(Version::V1_0),
+            "HTTP/1.1" => Ok(Version::V1_1),
+            "HTTP/2.0" | "HTTP/2" => Ok(Version::V2_0),
+            other => Err(serde::de::Error::custom(format!("unsupported HTTP version: {other:?}"))),
+        }
+    }
+}
+
 impl Version {
-    pub fn to_string(&self) -> String {
-        let slice = match self {
-            Version::V0_9 => "",
-            Version::V1_0 => "HTTP/1.0",
-            Version::V1_1 => "HTTP/1.1",
-        };
-        return slice.to_string();
+    /// The major and minor version numbers, e.g. `(1, 1)` for HTTP/1.1
+    pub fn as_tuple(&self) -> (u8, u8) {
+        match self {
+            Version::V0_9 => (0, 9),
+            Version::V1_0 => (1, 0),
+            Version::V1_1 => (1, 1),
+            Version::V2_0 => (2, 0),
+        }
+    }
+
+    /// The version with the given major and minor numbers, e.g. `(1, 0)` for HTTP/1.0. `None` for unsupported versions.
+    pub fn try_from_tuple(tuple: (u8, u8)) -> Option<Self> {
+        match tuple {
+            (0, 9) => Some(Version::V0_9),
+            (1, 0) => Some(Version::V1_0),
+            (1, 1) => Some(Version::V1_1),
+            (2, 0) => Some(Version::V2_0),
+            _ => None,
+        }
     }
 
     /// Take the first line of the **request** packet and determine the HTTP version. Version 0.9 does not specify a version (e.g. `GET /some/path`).
+    ///
+    /// Whitespace around the line is ignored.
     pub fn try_from_first_req_line(first_line: &str) -> Result<Self, PacketErr> {
-        let mut parts: Vec<&str> = first_line.trim().split_whitespace().collect();
-        parts.retain(|p| p.trim().len() != 0); // filter out empty strings if needed
+        let parts: Vec<&str> = first_line.split_whitespace().collect();
                
         match parts.len() {
             2 => {
@@ -57,24 +101,37 @@ impl Version {
         match parts[2] {
             "HTTP/1.1" => Ok(Self::V1_1),
             "HTTP/1.0" => Ok(Self::V1_0),
+            "HTTP/2.0" | "HTTP/2" => Ok(Self::V2_0),
             _ => Err(PacketErr::InvalidHttpVersion) // invalid
         }
     }
 
+    /// Determine the HTTP version from the first line of either a request or a response packet.
+    ///
+    /// Lines starting with `HTTP/` are treated as response status lines, anything else as a request line.
+    pub fn try_from_first_line(first_line: &str) -> Result<Self, PacketErr> {
+        if first_line.trim_start().starts_with("HTTP/") {
+            Self::try_from_first_res_line(first_line)
+        } else {
+            Self::try_from_first_req_line(first_line)
+        }
+    }
+
     /// Try to get the HTTP version from the first line of a **response packet**.
     /// Only the first line is expected
     pub fn try_from_first_res_line(s: &str) -> Result<Self, PacketErr> {
         let parts: Vec<&str> = s.split_whitespace().collect();
         
-        // 3 parts expected
-        // E.g. `HTTP/1.0 200 OK`
-        if parts.len() != 3 {
+        // At least 3 parts expected, the reason phrase may span several words
+        // E.g. `HTTP/1.0 200 OK` or `HTTP/1.0 404 Not Found`
+        if parts.len() < 3 {
             return Err(PacketErr::FirstLineWordCountMismatch);
         }
 
         match parts[0] {
             "HTTP/1.0" => Ok(Self::V1_0),
             "HTTP/1.1" => Ok(Self::V1_1),
+            "HTTP/2.0" | "HTTP/2" => Ok(Self::V2_0),
             _ => Err(PacketErr::NoVersionFound)
         }
     }
@@ -82,6 +139,7 @@ impl Version {
 
 
 #[cfg(test)]
+#[allow(non_snake_case)]
 mod req_version_test {
     use super::*;
 
@@ -89,8 +147,33 @@ mod req_version_test {
     fn none1() {
         assert_eq!(
             Err(PacketErr::InvalidHttpVersion),
+            Version::try_from_first_req_line("GET /api HTTP/3.0")
+        );
+    }
+
+    #[test]
+    fn valid_2_0__1() {
+        assert_eq!(
+            Ok(Version::V2_0),
             Version::try_from_first_req_line("GET /api HTTP/2.0")
         );
+        assert_eq!(
+            Ok(Version::V2_0),
+            Version::try_from_first_res_line("HTTP/2.0 200 OK")
+        );
+    }
+
+    #[test]
+    fn first_line() {
+        assert_eq!(Ok(Version::V2_0), Version::try_from_first_line("GET / HTTP/2.0"));
+        assert_eq!(Ok(Version::V2_0), Version::try_from_first_line("HTTP/2.0 404 Not Found"));
+        assert_eq!(Ok(Version::V1_0), Version::try_from_first_line("HTTP/1.0 200 OK"));
+        assert_eq!(Ok(Version::V0_9), Version::try_from_first_line("GET /"));
+    }
+
+    #[test]
+    fn display_2_0() {
+        assert_eq!(Version::V2_0.to_string(), "HTTP/2.0");
     }
 
     #[test]
@@ -125,3 +208,34 @@ mod req_version_test {
         );
     }
 }
+
+#[cfg(test)]
+mod version_order_tests {
+    use super::*;
+
+    #[test]
+    fn ordered_oldest_first() {
+        assert!(Version::V0_9 < Version::V1_0);
+        assert!(Version::V1_0 < Version::V1_1);
+        assert!(Version::V1_1 < Version::V2_0);
+        assert!(Version::V1_1 >= Version::V1_1);
+        assert_eq!(Version::V1_0.max(Version::V1_1), Version::V1_1);
+    }
+}
+
+#[cfg(test)]
+mod version_tuple_tests {
+    use super::*;
+
+    #[test]
+    fn tuples() {
+        assert_eq!(Version::V0_9.as_tuple(), (0, 9));
+        assert_eq!(Version::V1_0.as_tuple(), (1, 0));
+        assert_eq!(Version::V1_1.as_tuple(), (1, 1));
+        for version in [Version::V0_9, Version::V1_0, Version::V1_1, Version::V2_0] {
+            assert_eq!(Version::try_from_tuple(version.as_tuple()), Some(version));
+        }
+        assert_eq!(Version::try_from_tuple((1, 2)), None);
+        assert_eq!(Version::try_from_tuple((3, 0)), None);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::obj::{Body, Method, Header, Version, StatusCode};
+use crate::obj::{Body, Method, Header, Version, StatusCode, StatusCodeInt, Link, Cookie};
+use crate::obj::uri::{percent_decode, percent_encode_component};
+use crate::date::parse_http_date;
 
 /// An error that occurs when building or parsing packets
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -23,12 +25,408 @@ pub enum PacketErr {
     MissingVersion,
     /// When the header can't be parsed. Includes the malformed header line.
     MalformedHeader(String),
+    /// When a header line of a parsed packet can't be parsed. Includes the 1-based line number (the first line of the packet being line 1) and the malformed header line.
+    MalformedHeaderAtLine(usize, String),
     /// When no `\r\n\r\n` sequence could be found in the packet. This is expected even if there are no headers.
     NoHeaderEndFound,
     /// When the HTTP version indicated in the packet is not supported or invalid
     InvalidHttpVersion,
     /// When the first line of a response packet (the status line) is malformed
     InvalidStatusLine,
+    /// When the `Transfer-Encoding` codings are invalid, e.g. `chunked` is not the final coding
+    InvalidTransferEncoding,
+    /// When a `Content-Encoding` coding is not supported. Includes the coding.
+    UnsupportedContentEncoding(String),
+    /// When the body could not be decoded with the given `Content-Encoding` coding. Includes the coding.
+    DecompressionFailed(String),
+    /// When the `Content-Length` header is not a number, or several `Content-Length` headers disagree
+    InvalidContentLength,
+    /// When a `chunked` body is not framed properly
+    MalformedChunkedBody,
+    /// When `Transfer-Encoding: chunked` is declared, but the body is not chunk-framed (e.g. it lacks the final `0\r\n\r\n`)
+    UnframedChunkedBody,
+    /// When the body is not valid UTF-8
+    NonUtf8Body,
+    /// When the URL of a request contains spaces or control characters
+    InvalidUrl,
+    /// When a `%` escape is malformed or the decoded bytes are not valid UTF-8. Includes a description of the problem.
+    InvalidPercentEncoding(String),
+    /// When a request target is in absolute-form (e.g. `http://example.com/`), but the receiver is an origin server and not a proxy
+    AbsoluteFormNotAllowed,
+    /// When a status code is outside of `100` to `599`. Includes the code.
+    InvalidStatusCode(u16),
+    /// When the URL of a request has spaces, leading or trailing whitespace or control characters, e.g. after it was changed by hand
+    InvalidRequestTarget,
+    /// When a body that is supposed to be JSON can't be parsed as JSON
+    InvalidJsonBody,
+    /// When a redirect chain is longer than allowed. Includes the maximum number of redirects.
+    TooManyRedirects(usize),
+    /// When a value could not be serialized to JSON. Includes the serializer's error message.
+    JsonSerializationFailed(String),
+    /// When a response has a body although its status doesn't allow one (`1xx`, `204` and `304`). Includes the status code.
+    BodyNotAllowedForStatus(StatusCodeInt),
+    /// When the method can't be used with the HTTP version of the request, i.e. anything but `GET` with HTTP/0.9
+    InvalidMethodForVersion,
+}
+
+/// Attaches the 1-based line number to a header parsing error
+fn with_line_number(err: PacketErr, line_number: usize) -> PacketErr {
+    match err {
+        PacketErr::MalformedHeader(line) => PacketErr::MalformedHeaderAtLine(line_number, line),
+        other => other,
+    }
+}
+
+/// Checks that a request URL can be written into a request line: no spaces and no control characters
+fn validate_url(url: &str) -> Result<(), PacketErr> {
+    if url.bytes().any(|b| b == b' ' || b.is_ascii_control()) {
+        return Err(PacketErr::InvalidUrl);
+    }
+    Ok(())
+}
+
+/// Parse the first line of a request packet (`METHOD URL VERSION`, or `METHOD URL` for HTTP/0.9) into its parts.
+///
+/// Whitespace around the line is ignored, so `   GET / HTTP/1.1` is accepted.
+/// The parts may be separated by any whitespace, including tabs (`GET\t/\tHTTP/1.1`).
+fn parse_request_line(line: &str) -> Result<(Method, String, Version), PacketErr> {
+    let line: &str = line.trim();
+
+    // Get HTTP version
+    let version: Version = Version::try_from_first_req_line(line)?;
+
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 2 || parts.len() > 3 {
+        return Err(PacketErr::FirstLineWordCountMismatch);
+    }
+
+    // now we know that we have 2 or 3 words in our first line
+    let method: Method = parts[0].parse()?;
+    let url: String = parts[1].to_string();
+    validate_url(&url)?;
+
+    Ok((method, url, version))
+}
+
+/// Interpret a header value as a boolean: `1`/`true`/`yes` and `0`/`false`/`no`, case-insensitive
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Values of every header named `name` (case-insensitive), in order of appearance
+fn header_values<'a, 'n>(headers: &'a [Header], name: &'n str) -> impl Iterator<Item = &'a str> + use<'a, 'n> {
+    headers
+        .iter()
+        .filter(move |h| h.key.trim().eq_ignore_ascii_case(name))
+        .map(|h| h.value.trim())
+}
+
+/// Whether both lists hold the same headers, ignoring order and the case of the names. Values must match exactly.
+fn headers_eq_ignore_case(a: &[Header], b: &[Header]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    fn normalized(headers: &[Header]) -> Vec<(String, &str)> {
+        let mut pairs: Vec<(String, &str)> = headers
+            .iter()
+            .map(|h| (h.key.trim().to_ascii_lowercase(), h.value.as_str()))
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+    normalized(a) == normalized(b)
+}
+
+/// Decode the body in the charset from the `Content-Type` header, or as UTF-8 without one.
+/// `None` if there is no body, or if it is not valid in that charset.
+#[cfg(feature = "encoding")]
+fn decode_text(headers: &[Header], body: Option<&Body>) -> Option<String> {
+    let body: &Body = body?;
+    let encoding: &'static encoding_rs::Encoding = header_values(headers, "Content-Type")
+        .next()
+        .into_iter()
+        .flat_map(|v| v.split(';').skip(1))
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        .and_then(|(_, label)| encoding_rs::Encoding::for_label(label.trim().trim_matches('"').as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding
+        .decode_without_bom_handling_and_without_replacement(body.as_bytes())
+        .map(|text| text.into_owned())
+}
+
+/// Turn the lone `\n` line endings of the start line and headers into `\r\n`. The body, after the first empty line, is kept as it is.
+fn normalize_head_line_endings(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + s.len() / 16);
+    let mut rest: &str = s;
+    while let Some((line, after)) = rest.split_once('\n') {
+        let line: &str = line.strip_suffix('\r').unwrap_or(line);
+        out.push_str(line);
+        out.push_str("\r\n");
+        rest = after;
+        if line.is_empty() {
+            break;
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The body with the `Content-Encoding` codings from `headers` undone, or `None` if there is no body
+#[cfg(feature = "compression")]
+fn decompress_body(headers: &[Header], body: Option<&Body>) -> Result<Option<Vec<u8>>, PacketErr> {
+    let Some(body) = body else {
+        return Ok(None);
+    };
+    let encodings: Vec<String> = codings(headers, "Content-Encoding");
+    crate::compression::decode(body.as_bytes(), &encodings).map(Some)
+}
+
+/// Replace the first header with the same name as `header` (case-insensitive) and remove the others, or append it if there is none
+fn set_header(headers: &mut Option<Vec<Header>>, header: Header) {
+    let headers: &mut Vec<Header> = headers.get_or_insert_with(Vec::new);
+    match headers.iter().position(|h| h.key.trim().eq_ignore_ascii_case(&header.key)) {
+        Some(first) => {
+            let mut index: usize = 0;
+            headers.retain(|h| {
+                let keep: bool = index <= first || !h.key.trim().eq_ignore_ascii_case(&header.key);
+                index += 1;
+                keep
+            });
+            headers[first] = header;
+        }
+        None => headers.push(header),
+    }
+}
+
+/// Ordered, lowercased codings from every header named `name`, e.g. `gzip, chunked` -> `["gzip", "chunked"]`
+fn codings(headers: &[Header], name: &str) -> Vec<String> {
+    header_values(headers, name)
+        .flat_map(|v| v.split(','))
+        .map(|c| c.trim().to_ascii_lowercase())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Ordered, lowercased codings from every `Transfer-Encoding` header
+fn transfer_encodings(headers: &[Header]) -> Vec<String> {
+    codings(headers, "Transfer-Encoding")
+}
+
+/// The declared `Content-Length`, if any. Repeated headers must agree.
+pub(crate) fn content_length(headers: &[Header]) -> Result<Option<usize>, PacketErr> {
+    let mut declared: Option<usize> = None;
+    for value in header_values(headers, "Content-Length") {
+        let len: usize = value.parse().map_err(|_| PacketErr::InvalidContentLength)?;
+        if declared.is_some_and(|d| d != len) {
+            return Err(PacketErr::InvalidContentLength);
+        }
+        declared = Some(len);
+    }
+    Ok(declared)
+}
+
+/// Decode a `chunked` body. Returns the decoded data and the number of bytes the framing took up (including the trailers).
+///
+/// Chunk extensions and trailer fields are skipped.
+fn decode_chunked(data: &[u8]) -> Result<(Vec<u8>, usize), PacketErr> {
+    // index of the next \r\n at or after `from`
+    let find_crlf = |from: usize| -> Option<usize> {
+        data.get(from..)?
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .map(|p| from + p)
+    };
+
+    let mut decoded: Vec<u8> = Vec::new();
+    let mut pos: usize = 0;
+
+    loop {
+        // chunk-size [; extensions] \r\n
+        let line_end: usize = find_crlf(pos).ok_or(PacketErr::MalformedChunkedBody)?;
+        let line: &str = std::str::from_utf8(&data[pos..line_end]).map_err(|_| PacketErr::MalformedChunkedBody)?;
+        let size_str: &str = line.split(';').next().unwrap_or("").trim();
+        let size: usize = usize::from_str_radix(size_str, 16).map_err(|_| PacketErr::MalformedChunkedBody)?;
+        pos = line_end + 2;
+
+        if size == 0 {
+            // trailer fields, terminated by an empty line
+            loop {
+                let trailer_end: usize = find_crlf(pos).ok_or(PacketErr::MalformedChunkedBody)?;
+                let empty: bool = trailer_end == pos;
+                pos = trailer_end + 2;
+                if empty {
+                    return Ok((decoded, pos));
+                }
+            }
+        }
+
+        // chunk-data \r\n
+        let chunk_end: usize = pos.checked_add(size).ok_or(PacketErr::MalformedChunkedBody)?;
+        if data.get(chunk_end..).and_then(|rest| rest.get(..2)) != Some(b"\r\n".as_slice()) {
+            return Err(PacketErr::MalformedChunkedBody);
+        }
+        decoded.extend_from_slice(&data[pos..chunk_end]);
+        pos = chunk_end + 2;
+    }
+}
+
+/// Extract the body from the bytes following the blank line that ends the headers.
+///
+/// `method` only tells a request body (`Some`, with the method of the request) apart from a response body (`None`).
+/// Its value doesn't matter: whether a request has a body is decided by its framing headers alone, whatever the method (RFC 7230 3.3).
+/// The body is framed by, in order of precedence:
+/// 1. `Transfer-Encoding` ending in `chunked`: the body is decoded
+/// 2. `Content-Length`: exactly that many bytes are taken, the rest is ignored
+/// 3. Neither: requests have no body, responses take all the remaining bytes
+///
+/// A body that is not valid UTF-8 is returned as [`Body::Bytes`].
+///
+/// Responses to `HEAD` requests and `1xx`/`204`/`304` responses never have a body, but that is left to the caller.
+pub fn parse_body(headers: &[Header], remaining: &[u8], method: Option<Method>) -> Result<Option<Body>, PacketErr> {
+    let Some(framed) = framed_body(headers, remaining, method.is_some())? else {
+        return Ok(None);
+    };
+    let bytes: Vec<u8> = if transfer_encodings(headers).last().is_some_and(|c| c == "chunked") {
+        decode_chunked(framed)?.0
+    } else {
+        framed.to_vec()
+    };
+
+    Ok(Some(Body::from_bytes(bytes)))
+}
+
+/// The part of `remaining` that is the body, with its chunk framing if it has any. Follows the rules of [`parse_body`].
+///
+/// `None` if there is no body: a request without framing headers, or an empty response without them.
+fn framed_body<'a>(headers: &[Header], remaining: &'a [u8], is_request: bool) -> Result<Option<&'a [u8]>, PacketErr> {
+    if transfer_encodings(headers).last().is_some_and(|c| c == "chunked") {
+        let (_, consumed) = decode_chunked(remaining)?;
+        Ok(Some(&remaining[..consumed]))
+    } else if let Some(len) = content_length(headers)? {
+        remaining.get(..len).map(Some).ok_or(PacketErr::InvalidLines)
+    } else if is_request || remaining.is_empty() {
+        Ok(None)
+    } else {
+        // A response without framing headers lasts until the connection closes
+        Ok(Some(remaining))
+    }
+}
+
+/// Whether a request target is in absolute-form, e.g. `http://example.com/path` (starts with a scheme followed by `://`)
+fn is_absolute_form(url: &str) -> bool {
+    match url.split_once("://") {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+/// The scheme and authority of an absolute-form URL, e.g. `("https", "example.com:8443")` for `https://example.com:8443/a?b`
+fn url_origin(url: &str) -> Option<(&str, &str)> {
+    if !is_absolute_form(url) {
+        return None;
+    }
+    let (scheme, rest) = url.split_once("://")?;
+    let authority: &str = rest.split(['/', '?', '#']).next().unwrap_or("");
+    Some((scheme, authority))
+}
+
+/// Resolve a `Location` header value against the URL of the request it redirects.
+///
+/// Handles absolute URLs, scheme-relative (`//host/path`), absolute paths, query-only and relative paths.
+/// `.` and `..` segments of the resulting path are removed.
+fn resolve_location(base: &str, location: &str) -> String {
+    if location.contains("://") {
+        return location.to_string();
+    }
+
+    // split the base into `scheme://authority` (empty for origin-form URLs) and the path
+    let (origin, base_path): (&str, &str) = match base.find("://") {
+        Some(scheme_end) => {
+            let path_start = base[scheme_end + 3..].find('/').map_or(base.len(), |i| scheme_end + 3 + i);
+            (&base[..path_start], &base[path_start..])
+        }
+        None => ("", base),
+    };
+    let base_path: &str = base_path.split(['?', '#']).next().unwrap_or("");
+
+    if let Some(rest) = location.strip_prefix("//") {
+        return match base.split_once("://") {
+            Some((scheme, _)) => format!("{scheme}://{rest}"),
+            None => location.to_string(),
+        };
+    }
+
+    let path: String = if location.starts_with('/') {
+        location.to_string()
+    } else if location.starts_with('?') || location.is_empty() {
+        format!("{base_path}{location}")
+    } else {
+        // replace the last segment of the base path
+        let dir: &str = base_path.rfind('/').map_or("/", |i| &base_path[..=i]);
+        format!("{dir}{location}")
+    };
+
+    // remove dot segments from the path, leaving the query untouched
+    let (path, query): (&str, &str) = match path.find(['?', '#']) {
+        Some(i) => path.split_at(i),
+        None => (path.as_str(), ""),
+    };
+    let mut segments: Vec<&str> = Vec::new();
+    let raw_segments: Vec<&str> = path.split('/').skip(1).collect();
+    for (i, segment) in raw_segments.iter().enumerate() {
+        let is_last: bool = i + 1 == raw_segments.len();
+        if *segment == ".." {
+            segments.pop();
+        }
+        if *segment != "." && *segment != ".." {
+            segments.push(segment);
+        } else if is_last {
+            // `a/b/..` resolves to `a/`
+            segments.push("");
+        }
+    }
+
+    format!("{origin}/{}{query}", segments.join("/"))
+}
+
+/// The offset in `s` where the start line and headers end, given `s` split into `lines` at `\r\n`
+/// and the index of the empty line ending the headers. This is where the body starts.
+///
+/// The `\r\n` after the empty line may be missing at the very end of `s`.
+fn head_end(lines: &[&str], index_header_end: usize, s: &str) -> usize {
+    lines[..=index_header_end]
+        .iter()
+        .map(|line| line.len() + 2)
+        .sum::<usize>()
+        .min(s.len())
+}
+
+/// Checks the header and framing rules shared by requests and responses
+fn validate_message(headers: &[Header], body: Option<&Body>) -> Result<(), PacketErr> {
+    // Per RFC 7230 3.3.1, `chunked` may only be applied once, and must be the final coding
+    let codings = transfer_encodings(headers);
+    if let Some(pos) = codings.iter().position(|c| c == "chunked")
+        && pos != codings.len() - 1 {
+        return Err(PacketErr::InvalidTransferEncoding);
+    }
+
+    // A body sent as `chunked` has to carry the chunk framing itself, ending with `0\r\n\r\n`
+    if codings.last().is_some_and(|c| c == "chunked")
+        && let Some(body) = body {
+        let bytes: &[u8] = body.as_bytes();
+        if !decode_chunked(bytes).is_ok_and(|(_, consumed)| consumed == bytes.len()) {
+            return Err(PacketErr::UnframedChunkedBody);
+        }
+    }
+    Ok(())
 }
 
 /// An HTTP request packet
@@ -43,6 +441,7 @@ pub enum PacketErr {
 /// Connection: keep-alive
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RequestPacket {
     pub method: Method,
     /// Aka the resource
@@ -52,48 +451,540 @@ pub struct RequestPacket {
     pub body: Option<Body>,
 }
 
+/// The maximum length in bytes of [`RequestPacket::summary`]
+pub const SUMMARY_MAX_LEN: usize = 100;
+
 impl RequestPacket {
-    /// Infallibly convert get a string representation of the packet
-    pub fn to_string(&self) -> String {
-        let mut res = String::new();
+    /// Start building a request, e.g. `RequestPacket::builder().get().path("/x").http_1_1().try_build()`
+    pub fn builder() -> RequestPacketBuilder {
+        RequestPacketBuilder::new()
+    }
 
-        // Start line: METHOD URL VERSION
-        res.push_str(
-            format!("{} {} {}\r\n", self.method.to_string(), self.url, self.version.to_string()).as_str()
+    /// Try to parse a finished request packet from a string. Fallible.
+    ///
+    /// Shorthand for [`RequestPacketBuilder::try_from_str`] followed by [`RequestPacketBuilder::try_build`].
+    pub fn try_from_str(s: &str) -> Result<RequestPacket, PacketErr> {
+        RequestPacketBuilder::try_from_str(s)?.try_build()
+    }
+
+    /// The number of bytes of the serialized packet, without serializing it
+    pub fn serialized_len(&self) -> usize {
+        // METHOD URL VERSION\r\n
+        let start_line: usize = self.method.to_string().len() + 1 + self.url.len() + 1 + self.version.to_string().len() + 2;
+        // Key: Value\r\n
+        let headers: usize = self.headers
+            .iter()
+            .map(|h| h.key.len() + 2 + h.value.len() + 2)
+            .sum();
+        let body: usize = self.body.as_ref().map(|b| b.len()).unwrap_or(0);
+        // the extra 2 bytes are the \r\n ending the headers
+        start_line + headers + 2 + body
+    }
+
+    /// Same as [`RequestPacket::try_from_str`], but also returns the number of bytes of `s` the packet occupied on the wire
+    /// (header block and body), as written by the sender.
+    ///
+    /// The body ends after `Content-Length` bytes or with the last chunk of a chunked body, and anything after it
+    /// is not part of the packet, so this is where the next one starts.
+    pub fn try_from_str_with_wire_size(s: &str) -> Result<(RequestPacket, usize), PacketErr> {
+        let (builder, wire_size) = RequestPacketBuilder::parse(s, true)?;
+        Ok((builder.try_build()?, wire_size))
+    }
+
+    /// Serialize the packet into bytes without consuming it, e.g. to keep it around for retries.
+    /// Same as converting it with `Vec::<u8>::from`, and works for binary bodies as well.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_capacity()
+    }
+
+    /// The serialized packet as escaped text on a single line, e.g. `GET / HTTP/1.1\r\nHost: a\r\n\r\n` with literal `\r\n`s.
+    ///
+    /// Tabs, line breaks, quotes and backslashes are escaped like in Rust, and other bytes outside of printable ASCII as `\xNN`,
+    /// so the result can be pasted into a byte string literal (`b"..."`) for docs or test fixtures.
+    pub fn to_wire_escaped(&self) -> String {
+        self.to_bytes().escape_ascii().to_string()
+    }
+
+    /// Serialize the packet into a single buffer allocated up front with [`RequestPacket::serialized_len`] bytes
+    pub fn to_bytes_with_capacity(&self) -> Vec<u8> {
+        let mut buf: Vec<u8> = Vec::with_capacity(self.serialized_len());
+
+        buf.extend_from_slice(self.method.to_string().as_bytes());
+        buf.push(b' ');
+        buf.extend_from_slice(self.url.as_bytes());
+        buf.push(b' ');
+        buf.extend_from_slice(self.version.to_string().as_bytes());
+        buf.extend_from_slice(b"\r\n");
+
+        for header in &self.headers {
+            buf.extend_from_slice(header.key.as_bytes());
+            buf.extend_from_slice(b": ");
+            buf.extend_from_slice(header.value.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+        buf.extend_from_slice(b"\r\n");
+
+        if let Some(body) = &self.body {
+            buf.extend_from_slice(body.as_bytes());
+        }
+
+        buf
+    }
+
+    /// Same as `to_string`, but fails with [`PacketErr::NonUtf8Body`] instead of mangling a binary body
+    pub fn try_to_string(&self) -> Result<String, PacketErr> {
+        if self.body.as_ref().is_some_and(|b| b.as_str().is_none()) {
+            return Err(PacketErr::NonUtf8Body);
+        }
+        Ok(self.to_string())
+    }
+
+    /// The trimmed value of the first header named `name`. Names are compared case-insensitively.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        header_values(&self.headers, name).next()
+    }
+
+    /// The trimmed values of every header named `name`, in order of appearance. Names are compared case-insensitively.
+    pub fn get_header_all(&self, name: &str) -> Vec<&str> {
+        header_values(&self.headers, name).collect()
+    }
+
+    /// The value of the `X-Request-Id` header, if any
+    pub fn request_id(&self) -> Option<&str> {
+        self.get_header("X-Request-Id")
+    }
+
+    /// The value of the first header named `key` as a boolean, for headers like `DNT` or `Upgrade-Insecure-Requests`.
+    ///
+    /// `1`/`true`/`yes` and `0`/`false`/`no` are recognized, case-insensitively. `None` if the header is missing or has another value.
+    pub fn header_bool(&self, key: &str) -> Option<bool> {
+        self.get_header(key).and_then(parse_bool)
+    }
+
+    /// The codings listed in the `Transfer-Encoding` header(s), in the order they were applied
+    pub fn transfer_encodings(&self) -> Vec<String> {
+        transfer_encodings(&self.headers)
+    }
+
+    /// The content codings from the `Accept-Encoding` header(s) with their q-values, in order of appearance,
+    /// e.g. `gzip;q=1.0, identity;q=0.5, *;q=0` -> `[("gzip", 1.0), ("identity", 0.5), ("*", 0.0)]`.
+    ///
+    /// Codings are lowercased and a missing q-value is `1.0`. Entries with a q-value that isn't a number between 0 and 1 are skipped.
+    pub fn accepted_encodings(&self) -> Vec<(String, f32)> {
+        header_values(&self.headers, "Accept-Encoding")
+            .flat_map(|v| v.split(','))
+            .filter_map(|item| {
+                let mut params = item.split(';');
+                let coding: String = params.next()?.trim().to_ascii_lowercase();
+                if coding.is_empty() {
+                    return None;
+                }
+                let mut q: f32 = 1.0;
+                for param in params {
+                    if let Some((name, value)) = param.split_once('=')
+                        && name.trim().eq_ignore_ascii_case("q")
+                    {
+                        q = value.trim().parse().ok().filter(|q| (0.0..=1.0).contains(q))?;
+                    }
+                }
+                Some((coding, q))
+            })
+            .collect()
+    }
+
+    /// Whether the client accepts the content coding `enc` according to `Accept-Encoding` (RFC 7231 5.3.4).
+    ///
+    /// A coding listed with `q=0` is not acceptable. Codings that aren't listed fall back to the `*` entry, if any.
+    /// Without an `Accept-Encoding` header any coding is acceptable, and `identity` is acceptable unless excluded explicitly or by `*;q=0`.
+    pub fn prefers_encoding(&self, enc: &str) -> bool {
+        if self.get_header("Accept-Encoding").is_none() {
+            return true;
+        }
+        let accepted: Vec<(String, f32)> = self.accepted_encodings();
+        let q_of = |coding: &str| accepted.iter().find(|(c, _)| c.eq_ignore_ascii_case(coding)).map(|(_, q)| *q);
+
+        match q_of(enc).or_else(|| q_of("*")) {
+            Some(q) => q > 0.0,
+            None => enc.eq_ignore_ascii_case("identity"),
+        }
+    }
+
+    /// Check the packet for semantic errors that parsing and building do not catch,
+    /// such as `chunked` not being the final transfer coding, or a `chunked` body without chunk framing.
+    ///
+    /// Also fails with [`PacketErr::InvalidRequestTarget`] if the URL has spaces, other leading or trailing whitespace
+    /// or control characters, which can happen when it is set on the packet directly.
+    pub fn validate(&self) -> Result<(), PacketErr> {
+        if self.url.trim() != self.url || self.url.chars().any(|c| c == ' ' || c.is_control()) {
+            return Err(PacketErr::InvalidRequestTarget);
+        }
+        validate_message(&self.headers, self.body.as_ref())
+    }
+
+    /// Whether the packet has the same headers as `other`, ignoring their order and the case of the names.
+    /// Repeated headers must appear the same number of times in both.
+    pub fn headers_eq_ignore_case(&self, other: &[Header]) -> bool {
+        headers_eq_ignore_case(&self.headers, other)
+    }
+
+    /// The lowercased header names in order of appearance, duplicates included.
+    ///
+    /// Useful for comparing against the header orderings of known clients.
+    pub fn header_order(&self) -> Vec<String> {
+        self.headers
+            .iter()
+            .map(|h| h.key.trim().to_ascii_lowercase())
+            .collect()
+    }
+
+    /// Whether this is a conditional request, i.e. it has an `If-Match`, `If-None-Match`, `If-Modified-Since`, `If-Unmodified-Since` or `If-Range` header
+    pub fn is_conditional(&self) -> bool {
+        const CONDITIONAL_HEADERS: [&str; 5] = [
+            "If-Match",
+            "If-None-Match",
+            "If-Modified-Since",
+            "If-Unmodified-Since",
+            "If-Range",
+        ];
+        CONDITIONAL_HEADERS
+            .iter()
+            .any(|name| self.get_header(name).is_some())
+    }
+
+    /// The value of the `Origin` header, if present
+    pub fn origin(&self) -> Option<&str> {
+        self.get_header("Origin")
+    }
+
+    /// Whether this is a CORS preflight request: an `OPTIONS` request with both `Origin` and `Access-Control-Request-Method` headers
+    pub fn is_cors_preflight(&self) -> bool {
+        self.method == Method::Options
+            && self.origin().is_some()
+            && self.get_header("Access-Control-Request-Method").is_some()
+    }
+
+    /// The full URI the request is aimed at (RFC 7230 5.5), e.g. `http://example.com/path` for `GET /path` with `Host: example.com`.
+    ///
+    /// The scheme is `https` if `secure`, `http` otherwise. An absolute-form target is returned as it is.
+    /// For `CONNECT` (authority-form) and `OPTIONS *` (asterisk-form) targets, the URI has no path.
+    /// `None` if the authority is needed but there is no non-empty `Host` header.
+    pub fn effective_request_uri(&self, secure: bool) -> Option<String> {
+        if is_absolute_form(&self.url) {
+            return Some(self.url.clone());
+        }
+        let scheme: &str = if secure { "https" } else { "http" };
+
+        if self.method == Method::Connect {
+            return Some(format!("{scheme}://{}", self.url));
+        }
+
+        let host: &str = self.get_header("Host").filter(|h| !h.is_empty())?;
+        let path: &str = if self.url == "*" { "" } else { &self.url };
+        Some(format!("{scheme}://{host}{path}"))
+    }
+
+    /// The body decoded in the charset from the `Content-Type` header (e.g. `text/plain; charset=ISO-8859-1`), falling back to UTF-8.
+    /// `encoding` feature needed.
+    ///
+    /// `None` if there is no body, or if it is not valid in that charset.
+    #[cfg(feature = "encoding")]
+    pub fn text_decoded(&self) -> Option<String> {
+        decode_text(&self.headers, self.body.as_ref())
+    }
+
+    /// The `name=value` pairs from the `Cookie` header(s) in order of appearance, e.g. `a=1; b=2` -> `[("a", "1"), ("b", "2")]`
+    pub fn cookies(&self) -> Vec<(String, String)> {
+        header_values(&self.headers, "Cookie")
+            .flat_map(Cookie::parse_cookie_header)
+            .collect()
+    }
+
+    /// The preferences from the `Prefer` header(s) (RFC 7240) in order of appearance, with their values if any,
+    /// e.g. `return=minimal, wait=100, respond-async` -> `[("return", Some("minimal")), ("wait", Some("100")), ("respond-async", None)]`.
+    ///
+    /// Names are lowercased and quoted values unquoted. Parameters after a `;` are ignored.
+    pub fn preferences(&self) -> Vec<(String, Option<String>)> {
+        header_values(&self.headers, "Prefer")
+            .flat_map(|v| v.split(','))
+            .filter_map(|pref| {
+                let pref: &str = pref.split(';').next().unwrap_or("").trim();
+                let (name, value) = match pref.split_once('=') {
+                    Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"').to_string())),
+                    None => (pref, None),
+                };
+                if name.is_empty() {
+                    return None;
+                }
+                Some((name.to_ascii_lowercase(), value))
+            })
+            .collect()
+    }
+
+    /// The body with the `Content-Encoding` codings undone, e.g. for a client that compresses uploads,
+    /// or `None` if there is no body. `compression` feature needed.
+    ///
+    /// Multiple codings (e.g. `Content-Encoding: gzip, br`) are decoded in reverse order.
+    #[cfg(feature = "compression")]
+    pub fn decompressed_body(&self) -> Result<Option<Vec<u8>>, PacketErr> {
+        decompress_body(&self.headers, self.body.as_ref())
+    }
+
+    /// Whether the client wants the connection kept open after this request.
+    ///
+    /// HTTP/1.1 and later connections are persistent unless `Connection: close` is sent. HTTP/1.0 connections are closed
+    /// unless `Connection: keep-alive` is sent, and HTTP/0.9 connections are always closed.
+    pub fn keep_alive(&self) -> bool {
+        let connection: Vec<String> = codings(&self.headers, "Connection");
+        match self.version {
+            Version::V0_9 => false,
+            Version::V1_0 => connection.iter().any(|c| c == "keep-alive"),
+            _ => !connection.iter().any(|c| c == "close"),
+        }
+    }
+
+    /// Fails with [`PacketErr::AbsoluteFormNotAllowed`] if the request target is in absolute-form, e.g. `GET http://example.com/ HTTP/1.1`.
+    ///
+    /// Only proxies are sent absolute-form targets (RFC 7230 5.3.2), so an origin server can use this to reject them.
+    pub fn reject_absolute_form(&self) -> Result<(), PacketErr> {
+        if is_absolute_form(&self.url) {
+            return Err(PacketErr::AbsoluteFormNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Returns a clone of the packet whose URL has everything from the first `?` removed, e.g. `/a?b=1` becomes `/a`
+    pub fn without_query(&self) -> RequestPacket {
+        let mut packet = self.clone();
+        packet.url = self.path().to_string();
+        packet
+    }
+
+    /// The part of the URL before the `?`, e.g. `/search` for `/search?q=rust`
+    pub fn path(&self) -> &str {
+        self.url.split('?').next().unwrap_or("")
+    }
+
+    /// A one-line description of the request for logs and metrics labels, e.g. `GET /index.html (2 headers, 0 body bytes)`.
+    ///
+    /// The path (without the query) is cut short with `...` so that the summary is at most [`SUMMARY_MAX_LEN`] bytes long.
+    pub fn summary(&self) -> String {
+        use std::fmt::Write;
+
+        let method: String = self.method.to_string();
+        let counts: String = format!(
+            " ({} headers, {} body bytes)",
+            self.headers.len(),
+            self.body.as_ref().map_or(0, Body::len)
         );
+
+        let mut path: &str = self.path();
+        let room: usize = SUMMARY_MAX_LEN.saturating_sub(method.len() + 1 + counts.len());
+        let truncated: bool = path.len() > room;
+        if truncated {
+            let mut end: usize = room.saturating_sub(3);
+            while !path.is_char_boundary(end) {
+                end -= 1;
+            }
+            path = &path[..end];
+        }
+
+        let mut summary = String::with_capacity(SUMMARY_MAX_LEN);
+        let _ = write!(summary, "{method} {path}{}{counts}", if truncated { "..." } else { "" });
+        summary
+    }
+
+    /// The `key=value` pairs of the query string, in order of appearance, with repeated keys kept.
+    ///
+    /// Keys and values are percent-decoded, with `+` decoded as a space. A key without `=` (`?flag`) gets an empty value.
+    /// Keys and values that can't be decoded are kept as they are.
+    pub fn query_params(&self) -> Vec<(String, String)> {
+        let Some((_, query)) = self.url.split_once('?') else {
+            return Vec::new();
+        };
+        // a fragment is not part of the query
+        let query: &str = query.split('#').next().unwrap_or("");
+        query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                let decode = |s: &str| {
+                    let s: String = s.replace('+', " ");
+                    percent_decode(&s).unwrap_or(s)
+                };
+                (decode(key), decode(value))
+            })
+            .collect()
+    }
+
+    /// The decoded value of the first query parameter named `key`, decoded as in [`RequestPacket::query_params`].
+    /// `None` if there is no such parameter. Keys are compared after decoding, case-sensitively.
+    pub fn query_param(&self, key: &str) -> Option<String> {
+        self.query_params()
+            .into_iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The query string with its parameters sorted by key, then value, and consistently re-encoded, e.g. `b=2&a=1&a=0` -> `a=0&a=1&b=2`.
+    /// Useful for request signatures and cache keys.
+    ///
+    /// Parameters are decoded as in [`RequestPacket::query_params`] and every key and value is encoded again
+    /// with only unreserved characters kept, so `?q=a+b` and `?q=a%20b` give the same result (`q=a%20b`).
+    /// `None` if the URL has no `?`.
+    pub fn canonical_query(&self) -> Option<String> {
+        if !self.url.contains('?') {
+            return None;
+        }
+        let mut params: Vec<(String, String)> = self.query_params();
+        params.sort_unstable();
+        let canonical: Vec<String> = params
+            .iter()
+            .map(|(key, value)| format!("{}={}", percent_encode_component(key), percent_encode_component(value)))
+            .collect();
+        Some(canonical.join("&"))
+    }
+
+    /// Returns the request to send when following a redirect to `location` (the `Location` header of a 3xx response).
+    ///
+    /// Relative locations are resolved against the URL of this request.
+    /// A `303 See Other`, or a `302 Found` answering anything but `GET` or `HEAD`, turns the request into a `GET`
+    /// without a body, and the `Content-Length`, `Content-Type` and `Transfer-Encoding` headers are dropped.
+    ///
+    /// If the location is on another origin (scheme and authority of an absolute-form URL, or the `Host` header),
+    /// the `Host` header is changed to the new authority, and the `Authorization`, `Proxy-Authorization` and `Cookie`
+    /// headers are dropped, so that credentials aren't sent to another host.
+    pub fn for_redirect(&self, location: &str, status: &StatusCode) -> RequestPacket {
+        let mut packet = self.clone();
+        packet.url = resolve_location(&self.url, location.trim());
+
+        if let Some((scheme, authority)) = url_origin(&packet.url) {
+            let same_origin: bool = match url_origin(&self.url) {
+                Some((old_scheme, old_authority)) => {
+                    old_scheme.eq_ignore_ascii_case(scheme) && old_authority.eq_ignore_ascii_case(authority)
+                }
+                None => self.get_header("Host").is_some_and(|host| host.eq_ignore_ascii_case(authority)),
+            };
+            if !same_origin {
+                packet.headers.retain(|h| {
+                    !["Authorization", "Proxy-Authorization", "Cookie"]
+                        .iter()
+                        .any(|name| h.key.trim().eq_ignore_ascii_case(name))
+                });
+                for host in packet.headers.iter_mut().filter(|h| h.key.trim().eq_ignore_ascii_case("Host")) {
+                    host.value = authority.to_string();
+                }
+            }
+        }
+
+        let switch_to_get: bool = match status {
+            StatusCode::SeeOther => self.method != Method::Head,
+            StatusCode::Found => !matches!(self.method, Method::Get | Method::Head),
+            _ => false,
+        };
+        if switch_to_get {
+            packet.method = Method::Get;
+            packet.body = None;
+            packet.headers.retain(|h| {
+                !["Content-Length", "Content-Type", "Transfer-Encoding"]
+                    .iter()
+                    .any(|name| h.key.trim().eq_ignore_ascii_case(name))
+            });
+        }
+        packet
+    }
+
+    /// Returns a clone of the packet with every header name lowercased, as HTTP/2 requires. Values are left as they are.
+    pub fn to_lowercase_headers(&self) -> RequestPacket {
+        let mut packet = self.clone();
+        for header in &mut packet.headers {
+            header.key = header.key.to_ascii_lowercase();
+        }
+        packet
+    }
+
+    /// Returns a clone of the packet with the given headers applied on top of the existing ones.
+    ///
+    /// Each override replaces every header with the same name (case-insensitive), or is appended if no such header exists.
+    pub fn with_headers_overridden(&self, overrides: &[Header]) -> RequestPacket {
+        let mut packet = self.clone();
+        for over in overrides {
+            match packet.headers.iter().position(|h| h.key.eq_ignore_ascii_case(&over.key)) {
+                Some(index) => {
+                    packet.headers[index] = over.clone();
+                    // drop any later duplicates of the overridden header
+                    let mut i = index + 1;
+                    while i < packet.headers.len() {
+                        if packet.headers[i].key.eq_ignore_ascii_case(&over.key) {
+                            packet.headers.remove(i);
+                        } else {
+                            i += 1;
+                        }
+                    }
+                }
+                None => {
+                    packet.headers.push(over.clone());
+                }
+            }
+        }
+        packet
+    }
+}
+
+/// Infallibly get a string representation of the packet.
+///
+/// The parts of the first line are always separated by single spaces, even if the packet was parsed from a line using tabs or several spaces.
+/// A binary body is converted lossily; use [`RequestPacket::try_to_string`] or convert into `Vec<u8>` to keep it intact.
+impl std::fmt::Display for RequestPacket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Start line: METHOD URL VERSION
+        write!(f, "{} {} {}\r\n", self.method, self.url, self.version)?;
     
         // Headers
         for header in &self.headers {
-            res.push_str(&format!("{}: {}\r\n", header.key, header.value));
+            write!(f, "{}: {}\r\n", header.key, header.value)?;
         }
 
         // End of headers
-        res.push_str("\r\n");
+        write!(f, "\r\n")?;
 
         // Body (if present)
         if let Some(body) = &self.body {
-            res.push_str(body.0.as_str());
+            write!(f, "{}", String::from_utf8_lossy(body.as_bytes()))?;
         }
 
         // No \r\n after the body
 
-        res
+        Ok(())
     }
 }
 
-impl Into<String> for RequestPacket {
-    fn into(self) -> String {
-        self.to_string()
+impl From<RequestPacket> for String {
+    fn from(packet: RequestPacket) -> String {
+        packet.to_string()
     }
 }
 
-impl Into<Vec<u8>> for RequestPacket {
-    fn into(self) -> Vec<u8> {
-        self.to_string().into_bytes()
+impl From<RequestPacket> for Vec<u8> {
+    fn from(packet: RequestPacket) -> Vec<u8> {
+        packet.to_bytes_with_capacity()
     }
 }
 
 
+/// Check that parsing a request, serializing it, and parsing the result again yields the same packet.
+///
+/// Returns `false` if either parse fails. Meant for fuzzing and round-trip tests.
+pub fn is_serialization_stable(s: &str) -> bool {
+    let Ok(first) = RequestPacket::try_from_str(s) else {
+        return false;
+    };
+    let Ok(second) = RequestPacket::try_from_str(&first.to_string()) else {
+        return false;
+    };
+    first == second
+}
+
 /// Transitive struct for building request packets.
 ///
 /// Gets consumed to yield a RequestPacket
@@ -104,11 +995,13 @@ pub struct RequestPacketBuilder {
     pub version: Option<Version>,
     pub headers: Option<Vec<Header>>,
     pub body: Option<Body>,
+    /// Set with `auto_content_length()`
+    auto_content_length: bool,
 }
 
 impl RequestPacketBuilder {
     pub fn new() -> Self {
-        return Self::default();
+        Self::default()
     }
 
     /// URL setter
@@ -122,25 +1015,92 @@ impl RequestPacketBuilder {
         self.method = Some(method);
         self
     }
-    
-    /// Header setter. Instantiates the header list or extends it.
-    pub fn headers(mut self, headers: Vec<Header>) -> Self {
-        match self.headers {
-            // Extend
-            Some(ref mut h) => { h.extend(headers); }
-            // Instantiate
-            None => { self.headers = Some(headers); }
-        }   
-        self
+
+    /// Same as [`RequestPacketBuilder::url`], for the request target
+    pub fn path(self, path: &str) -> Self {
+        self.url(path)
     }
-        
-    /// Header setter. Intantiates the list or adds a new header to it.
-    pub fn header<T>(mut self, header_pair: (T, T)) -> Self  
+
+    /// Sets the method to `GET`
+    pub fn get(self) -> Self {
+        self.method(Method::Get)
+    }
+
+    /// Sets the method to `HEAD`
+    pub fn head(self) -> Self {
+        self.method(Method::Head)
+    }
+
+    /// Sets the method to `OPTIONS`
+    pub fn options(self) -> Self {
+        self.method(Method::Options)
+    }
+
+    /// Sets the method to `TRACE`
+    pub fn trace(self) -> Self {
+        self.method(Method::Trace)
+    }
+
+    /// Sets the method to `PUT`
+    pub fn put(self) -> Self {
+        self.method(Method::Put)
+    }
+
+    /// Sets the method to `DELETE`
+    pub fn delete(self) -> Self {
+        self.method(Method::Delete)
+    }
+
+    /// Sets the method to `POST`
+    pub fn post(self) -> Self {
+        self.method(Method::Post)
+    }
+
+    /// Sets the method to `PATCH`
+    pub fn patch(self) -> Self {
+        self.method(Method::Patch)
+    }
+
+    /// Sets the method to `CONNECT`
+    pub fn connect(self) -> Self {
+        self.method(Method::Connect)
+    }
+
+    /// Sets the version to HTTP/0.9
+    pub fn http_0_9(self) -> Self {
+        self.version(Version::V0_9)
+    }
+
+    /// Sets the version to HTTP/1.0
+    pub fn http_1_0(self) -> Self {
+        self.version(Version::V1_0)
+    }
+
+    /// Sets the version to HTTP/1.1
+    pub fn http_1_1(self) -> Self {
+        self.version(Version::V1_1)
+    }
+
+    /// Sets the version to HTTP/2.0
+    pub fn http_2_0(self) -> Self {
+        self.version(Version::V2_0)
+    }
+    
+    /// Header setter. Instantiates the header list or extends it.
+    pub fn headers(mut self, headers: Vec<Header>) -> Self {
+        match self.headers {
+            // Extend
+            Some(ref mut h) => { h.extend(headers); }
+            // Instantiate
+            None => { self.headers = Some(headers); }
+        }   
+        self
+    }
+        
+    /// Header setter. Intantiates the list or adds a new header to it.
+    pub fn header<T>(mut self, header_pair: (T, T)) -> Self  
     where T: Into<String> {
-        let h = Header {
-            key: header_pair.0.into(),
-            value: header_pair.1.into()
-        };
+        let h = Header::new(header_pair.0, header_pair.1);
         match self.headers {
             Some(ref mut hdrs) => {
                 hdrs.push(h);
@@ -153,6 +1113,28 @@ impl RequestPacketBuilder {
 
         self
     }
+
+    /// Header setter that replaces every existing header of the same name (compared case-insensitively), or adds it if there is none.
+    ///
+    /// Use this for headers that must be unique, like `Host` or `Content-Length`.
+    pub fn set_header<T>(mut self, header_pair: (T, T)) -> Self
+    where T: Into<String> {
+        set_header(&mut self.headers, Header::new(header_pair.0, header_pair.1));
+        self
+    }
+
+    /// Removes every header named `name` (compared case-insensitively). Does nothing if there is none.
+    pub fn remove_header(mut self, name: &str) -> Self {
+        if let Some(ref mut hdrs) = self.headers {
+            hdrs.retain(|h| !h.key.trim().eq_ignore_ascii_case(name));
+        }
+        self
+    }
+
+    /// Whether a header named `name` (compared case-insensitively) has been added
+    pub fn has_header(&self, name: &str) -> bool {
+        self.headers.as_deref().is_some_and(|hdrs| header_values(hdrs, name).next().is_some())
+    }
     
     /// Version setter
     pub fn version(mut self, version: Version) -> Self {
@@ -163,15 +1145,64 @@ impl RequestPacketBuilder {
     /// Body setter
     pub fn body<T>(mut self, body: T) -> Self 
     where T: std::fmt::Display {
-        self.body = Some(Body(format!("{body}")));
+        self.body = Some(Body::Text(format!("{body}")));
         self
     }
 
-    /// Sets the `Content-Length` header. If there is no body, does not set anything
+    /// Sets an already serialized JSON body, along with `Content-Type: application/json` and `Content-Length`.
+    ///
+    /// Fails with [`PacketErr::InvalidJsonBody`] if `json` is not valid JSON. `json` feature needed.
+    #[cfg(feature = "json")]
+    pub fn json_str(mut self, json: &str) -> Result<Self, PacketErr> {
+        let body: Body = Body::from(json);
+        if !body.is_valid_json() {
+            return Err(PacketErr::InvalidJsonBody);
+        }
+        let len: String = body.len().to_string();
+        self.body = Some(body);
+        Ok(self
+            .set_header(("Content-Type", "application/json"))
+            .set_header(("Content-Length", len.as_str())))
+    }
+
+    /// Serializes `value` to JSON and sets it as the body, along with `Content-Type: application/json` and `Content-Length`.
+    /// `serde` and `json` features needed.
+    ///
+    /// Fails with [`PacketErr::JsonSerializationFailed`] if `value` can't be serialized, e.g. a map with non-string keys.
+    #[cfg(all(feature = "serde", feature = "json"))]
+    pub fn json_body<T: serde::Serialize>(mut self, value: &T) -> Result<Self, PacketErr> {
+        let json: String = serde_json::to_string(value).map_err(|e| PacketErr::JsonSerializationFailed(e.to_string()))?;
+        let len: String = json.len().to_string();
+        self.body = Some(Body::Text(json));
+        Ok(self
+            .set_header(("Content-Type", "application/json"))
+            .set_header(("Content-Length", len.as_str())))
+    }
+
+    /// Sets the `X-Request-Id` header, used to correlate requests and responses in logs and traces
+    pub fn request_id(self, id: &str) -> Self {
+        self.header(("X-Request-Id", id))
+    }
+
+    /// Sets the `Prefer` header from `(name, value)` pairs, e.g. `[("return", Some("minimal")), ("wait", Some("100"))]`
+    /// for `Prefer: return=minimal, wait=100`. Replaces any `Prefer` header set before.
+    pub fn preferences(self, prefs: &[(&str, Option<&str>)]) -> Self {
+        let value: String = prefs
+            .iter()
+            .map(|(name, value)| match value {
+                Some(value) => format!("{name}={value}"),
+                None => name.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        self.set_header(("Prefer", value.as_str()))
+    }
+
+    /// Sets the `Content-Length` header to the byte length of the current body. If there is no body, does not set anything
     pub fn content_length(mut self) -> Self {
         match self.body {
             Some(ref body) => {
-                let byte_count: usize = body.0.len();
+                let byte_count: usize = body.len();
                 self = self.header(("Content-Length", format!("{byte_count}").as_str()));
                 self
             }
@@ -181,68 +1212,77 @@ impl RequestPacketBuilder {
         }
     }
 
+    /// Let `try_build` set the `Content-Length` header from the final body, unless a `Content-Length` header has been set explicitly
+    /// or the body is sent `chunked`, since a message with both would have ambiguous framing
+    pub fn auto_content_length(mut self) -> Self {
+        self.auto_content_length = true;
+        self
+    }
+
     /// Try to convert the builder into a request packet. Fails if the method, URL or version is missing.
-    pub fn try_build(self) -> Result<RequestPacket, PacketErr> {
+    pub fn try_build(mut self) -> Result<RequestPacket, PacketErr> {
         // required fields
-        if let None = self.method { 
+        if self.method.is_none() { 
             return Err(PacketErr::MissingMethod); 
         }
-        if let None = self.url { 
+        if self.url.is_none() { 
             return Err(PacketErr::MissingURL); 
         }
-        if let None = self.version { 
+        if self.version.is_none() { 
             return Err(PacketErr::MissingVersion);
         }
-        
+        validate_url(self.url.as_deref().unwrap_or(""))?;
+        // HTTP/0.9 only knows `GET`
+        if self.version == Some(Version::V0_9) && self.method != Some(Method::Get) {
+            return Err(PacketErr::InvalidMethodForVersion);
+        }
+
+        // Inject `Content-Length` if requested and not set explicitly, and the body is not framed by `chunked`
+        if self.auto_content_length
+            && self.body.is_some()
+            && !self.headers.iter().flatten().any(|h| h.key.trim().eq_ignore_ascii_case("Content-Length"))
+            && transfer_encodings(self.headers.as_deref().unwrap_or(&[])).last().is_none_or(|c| c != "chunked") {
+            self = self.content_length();
+        }
+
         Ok(RequestPacket {
             method: self.method.unwrap(),
             url: self.url.unwrap(),
             version: self.version.unwrap(),
-            headers: self.headers.unwrap_or(Vec::new()),
+            headers: self.headers.unwrap_or_default(),
             body: self.body,
         })
     }
 
     /// Try to parse packet builder from a string. Fallible.
     pub fn try_from_str(s: &str) -> Result<Self, PacketErr> {
-        let mut lines: Vec<&str> = s.split("\r\n").collect::<Vec<&str>>();
-        lines
-            .iter_mut()
-            .map(|l| l.trim())
-            .collect::<Vec<&str>>()
-            .retain(|l| l.len() != 0);
-
-        if lines.len() == 0 {
-            return Err(PacketErr::InvalidLines);
-        }
+        Self::parse(s, true).map(|(builder, _)| builder)
+    }
 
-        let first_line: &str = lines[0];
-        
-        // Get HTTP version
-        let version: Version = Version::try_from_first_req_line(first_line)?;
+    /// Same as [`RequestPacketBuilder::try_from_str`], but also accepts bare `\n` line endings in the start line and headers,
+    /// as sent by some clients and found in test fixtures. The body is not changed.
+    pub fn try_from_str_lenient(s: &str) -> Result<Self, PacketErr> {
+        Self::try_from_str(&normalize_head_line_endings(s))
+    }
 
-        // Get method
-        let fl_parts: Vec<&str> = first_line.split_whitespace()
-            .map(|x| x.trim())
-            .filter(|x| x.len() > 0)
-            .collect::<Vec<_>>();
-        if fl_parts.len() < 2 {
-            // We only have one word 
-            return Err(PacketErr::FirstLineWordCountMismatch);
-        } else if fl_parts.len() > 3 {
-            return Err(PacketErr::FirstLineWordCountMismatch);
-        }
+    /// Parse only the request line and headers, up to the empty line ending them. Anything after it is ignored,
+    /// so the body announced by `Content-Length` does not have to be there yet.
+    #[cfg(feature = "reader")]
+    pub(crate) fn try_from_head(s: &str) -> Result<Self, PacketErr> {
+        Self::parse(s, false).map(|(builder, _)| builder)
+    }
 
-        // now we know that we have 2 or 3 words in our first line
-        let method_str = fl_parts[0];
-        let method_opt: Option<Method> = Method::try_from(method_str);
-        if let None = method_opt {
-            return Err(PacketErr::InvalidMethod);
+    /// Parse a request, and also return the number of bytes of `s` it occupied (request line, headers and body).
+    /// Without `with_body`, only the request line and headers are parsed.
+    fn parse(s: &str, with_body: bool) -> Result<(Self, usize), PacketErr> {
+        let lines: Vec<&str> = s.split("\r\n").collect::<Vec<&str>>();
+
+        if lines.is_empty() {
+            return Err(PacketErr::InvalidLines);
         }
-        let method = method_opt.unwrap();
 
-        // url
-        let url = fl_parts[1];
+        let first_line: &str = lines[0];
+        let (method, url, version) = parse_request_line(first_line)?;
 
         // Headers
         // The list of lines will have a "" entry -> that is where the headers end
@@ -271,44 +1311,49 @@ impl RequestPacketBuilder {
             if index == 0 {
                 continue; // skip the first line
             }
-            if *line == "" {
+            if line.is_empty() {
                 break; // we are done with the header lines
             }
             
             let header_opt: Result<Header, PacketErr> = Header::try_from(*line);
-            let header = header_opt?;
+            let header = header_opt.map_err(|e| with_line_number(e, index + 1))?;
 
             headers.push(header);
         }
 
         // Body
-        // The last "line" (where the line break is \r\n) is the body
         // NOTE: Normally, a body cannot have a \r\n sequence. But if it happens, I would like this library to be smart enough to understand that it's a part of the body
         
         // get the index of the "" (the first one) -> that is where the headers end
         let index_header_end: usize = lines
             .iter()
-            .position(|x| *x == "")
+            .position(|x| x.is_empty())
             .expect("Internal Error: Could not find `\"\"` in the list of lines");
-        let body_start_index = index_header_end + 1;
-        // remove all the lines before this one
-        // (inclusive exclusive)
-        lines = lines.drain(0..body_start_index).collect();
-        let body_str = lines.join("\r\n");
-        let body: Option<Body> = match body_str.as_str() {
-            "" => None,
-            s => Some(Body(s.to_string()))
+        let body_start: usize = head_end(&lines, index_header_end, s);
+
+        // The body is framed the same way as by `parse_body`, but a chunked body keeps its framing.
+        // Anything after it is not part of this packet
+        let framed: &[u8] = if with_body {
+            framed_body(&headers, &s.as_bytes()[body_start..], true)?.unwrap_or_default()
+        } else {
+            content_length(&headers)?;
+            &[]
+        };
+        // the length may end in the middle of a character, so the body is not necessarily text
+        let body: Option<Body> = match framed {
+            [] => None,
+            b => Some(Body::from_bytes(b.to_vec())),
         };
-        
 
-        
-        Ok(Self {
+        let req = Self {
             body,
             version: Some(version),
             method: Some(method),
-            url: Some(url.to_string()),
+            url: Some(url),
             headers: Some(headers),
-        })
+            auto_content_length: false,
+        };
+        Ok((req, body_start + framed.len()))
     }
 }
 
@@ -337,6 +1382,675 @@ mod request_packet_test {
 
         assert_eq!(str_repr, rp.to_string());
     }
+
+    #[test]
+    fn try_from_str_full_packet() {
+        let input = "GET /index.html HTTP/1.1\r\nHost: www.example.com\r\nContent-Length: 4\r\n\r\nping";
+        let rp = RequestPacket::try_from_str(input).unwrap();
+        assert_eq!(
+            rp,
+            RequestPacket {
+                method: Method::Get,
+                url: "/index.html".into(),
+                version: Version::V1_1,
+                headers: vec![
+                    Header { key: "Host".into(), value: "www.example.com".into() },
+                    Header { key: "Content-Length".into(), value: "4".into() },
+                ],
+                body: Some(Body::Text("ping".into())),
+            }
+        );
+        assert_eq!(rp.to_string(), input);
+    }
+
+    #[test]
+    fn try_from_str_invalid() {
+        assert_eq!(
+            RequestPacket::try_from_str("fetch /index.html HTTP/1.1\r\n\r\n"),
+            Err(PacketErr::InvalidMethod)
+        );
+    }
+
+    #[test]
+    fn try_from_str_extension_method() {
+        let input = "PROPFIND /files HTTP/1.1\r\nDepth: 1\r\n\r\n";
+        let rp = RequestPacket::try_from_str(input).unwrap();
+        assert_eq!(rp.method, Method::Extension("PROPFIND".to_string()));
+        assert_eq!(rp.to_string(), input);
+    }
+
+    #[test]
+    fn headers_overridden() {
+        let rp = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url("/")
+            .version(Version::V1_1)
+            .header(("Host", "example.com"))
+            .header(("Accept", "text/html"))
+            .try_build()
+            .unwrap();
+
+        let overridden = rp.with_headers_overridden(&[
+            Header { key: "accept".into(), value: "application/json".into() },
+            Header { key: "User-Agent".into(), value: "httpsplitter".into() },
+        ]);
+
+        assert_eq!(
+            overridden.headers,
+            vec![
+                Header { key: "Host".into(), value: "example.com".into() },
+                Header { key: "accept".into(), value: "application/json".into() },
+                Header { key: "User-Agent".into(), value: "httpsplitter".into() },
+            ]
+        );
+        // the original is left untouched
+        assert_eq!(rp.headers.len(), 2);
+        assert_eq!(rp.headers[1].value, "text/html");
+    }
+
+    #[test]
+    fn serialized_bytes() {
+        let rp = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/upload")
+            .version(Version::V1_1)
+            .header(("Host", "example.com"))
+            .body("hello")
+            .content_length()
+            .try_build()
+            .unwrap();
+
+        let bytes = rp.to_bytes_with_capacity();
+        assert_eq!(bytes, rp.to_string().into_bytes());
+        assert_eq!(bytes.len(), rp.serialized_len());
+    }
+
+    #[test]
+    fn wire_size_matches_input() {
+        let input = "POST /submit HTTP/1.1\r\nHost: example.com\r\nContent-Length: 5\r\n\r\nhello";
+        let (_, wire_size) = RequestPacket::try_from_str_with_wire_size(input).unwrap();
+        assert_eq!(wire_size, input.len());
+
+        // not written the way this crate serializes it
+        let input = "GET / HTTP/1.1\r\nHost:a\r\n\r\n";
+        let (rp, wire_size) = RequestPacket::try_from_str_with_wire_size(input).unwrap();
+        assert_eq!(wire_size, input.len());
+        assert_eq!(rp.serialized_len(), input.len() + 1);
+
+        // the next request starts after the body
+        let input = "POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhelloGET / HTTP/1.1\r\n\r\n";
+        let (_, wire_size) = RequestPacket::try_from_str_with_wire_size(input).unwrap();
+        assert_eq!(&input[wire_size..], "GET / HTTP/1.1\r\n\r\n");
+
+        // a chunked body ends with the last chunk
+        let input = "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nGET /next HTTP/1.1\r\n\r\n";
+        let (_, wire_size) = RequestPacket::try_from_str_with_wire_size(input).unwrap();
+        assert_eq!(wire_size, 52);
+        assert_eq!(&input[wire_size..], "GET /next HTTP/1.1\r\n\r\n");
+
+        // the input may end right after the empty line
+        let input = "GET / HTTP/1.1\r\n";
+        assert_eq!(RequestPacket::try_from_str_with_wire_size(input).unwrap().1, input.len());
+    }
+
+    #[test]
+    fn to_bytes_matches_into() {
+        let mut rp = RequestPacket::try_from_str("PUT /file HTTP/1.1\r\nHost: example.com\r\nContent-Length: 3\r\n\r\nabc").unwrap();
+        assert_eq!(rp.to_bytes(), Vec::<u8>::from(rp.clone()));
+
+        rp.body = Some(Body::Bytes(vec![0xff, 0x00, 0xfe]));
+        let bytes: Vec<u8> = rp.to_bytes();
+        assert_eq!(bytes, Vec::<u8>::from(rp.clone()));
+        // the packet is still usable
+        assert_eq!(rp.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn wire_escaped_binary_body() {
+        let mut rp = RequestPacket::try_from_str("POST /img HTTP/1.1\r\nContent-Length: 5\r\n\r\n.....").unwrap();
+        rp.body = Some(Body::Bytes(vec![0x89, b'P', b'\n', b'"', 0x00]));
+        assert_eq!(
+            rp.to_wire_escaped(),
+            r#"POST /img HTTP/1.1\r\nContent-Length: 5\r\n\r\n\x89P\n\"\x00"#
+        );
+    }
+
+    #[test]
+    fn serialized_bytes_multi_byte_body() {
+        let rp = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/")
+            .version(Version::V1_0)
+            .body("h\u{e9}llo \u{1f980}")
+            .try_build()
+            .unwrap();
+
+        let bytes = rp.to_bytes_with_capacity();
+        assert_eq!(bytes.len(), rp.serialized_len());
+        assert!(bytes.ends_with("h\u{e9}llo \u{1f980}".as_bytes()));
+        assert_eq!(bytes, rp.to_string().into_bytes());
+    }
+
+    #[test]
+    fn binary_body_round_trip() {
+        let payload: Vec<u8> = vec![0x1f, 0x8b, 0x08, 0x00, 0xff, 0xfe, b'\r', b'\n'];
+        let mut builder = RequestPacketBuilder::new()
+            .method(Method::Put)
+            .url("/upload")
+            .version(Version::V1_1);
+        builder.body = Some(Body::from_bytes(payload.clone()));
+        let rp = builder.content_length().try_build().unwrap();
+
+        let bytes: Vec<u8> = rp.clone().into();
+        assert_eq!(bytes.len(), rp.serialized_len());
+        assert!(bytes.ends_with(&payload));
+        assert_eq!(rp.try_to_string(), Err(PacketErr::NonUtf8Body));
+        assert_eq!(rp.get_header("Content-Length"), Some("8"));
+    }
+
+    #[test]
+    fn get_header_case_insensitive() {
+        let rp = RequestPacket::try_from_str(
+            "GET / HTTP/1.1\r\nHost: example.com\r\nAccept: text/html\r\nAccept:  application/json \r\n\r\n"
+        ).unwrap();
+        assert_eq!(rp.get_header("host"), Some("example.com"));
+        assert_eq!(rp.get_header("HOST"), Some("example.com"));
+        assert_eq!(rp.get_header("Accept"), Some("text/html"));
+        assert_eq!(rp.get_header_all("accept"), vec!["text/html", "application/json"]);
+        assert_eq!(rp.get_header("Missing"), None);
+        assert!(rp.get_header_all("Missing").is_empty());
+    }
+
+    #[test]
+    fn conditional_requests() {
+        let conditional_headers = [
+            ("If-Match", "\"abc\""),
+            ("If-None-Match", "\"abc\""),
+            ("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT"),
+            ("If-Unmodified-Since", "Sun, 06 Nov 1994 08:49:37 GMT"),
+            ("if-range", "\"abc\""),
+        ];
+        for (key, value) in conditional_headers {
+            let rp = RequestPacketBuilder::new()
+                .method(Method::Get)
+                .url("/")
+                .version(Version::V1_1)
+                .header((key, value))
+                .try_build()
+                .unwrap();
+            assert!(rp.is_conditional(), "{key} should make the request conditional");
+        }
+
+        let unconditional = RequestPacket::try_from_str("GET / HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        assert!(!unconditional.is_conditional());
+    }
+
+    #[test]
+    fn header_order() {
+        let rp = RequestPacket::try_from_str(concat!(
+            "GET / HTTP/1.1\r\n",
+            "Host: example.com\r\n",
+            "User-Agent: Mozilla/5.0\r\n",
+            "Accept: text/html\r\n",
+            "Accept-Language: en-US\r\n",
+            "Accept-Encoding: gzip, deflate, br\r\n",
+            "Connection: keep-alive\r\n",
+            "\r\n",
+        )).unwrap();
+        assert_eq!(
+            rp.header_order(),
+            vec!["host", "user-agent", "accept", "accept-language", "accept-encoding", "connection"]
+        );
+    }
+
+    #[test]
+    fn lowercase_headers() {
+        let rp = RequestPacket::try_from_str("GET / HTTP/1.1\r\nHost: Example.COM\r\nX-Custom-Header: MiXeD\r\n\r\n").unwrap();
+        let lower = rp.to_lowercase_headers();
+        assert_eq!(
+            lower.headers,
+            vec![
+                Header::try_from("host: Example.COM").unwrap(),
+                Header::try_from("x-custom-header: MiXeD").unwrap(),
+            ]
+        );
+        assert_eq!(lower.url, rp.url);
+    }
+
+    #[test]
+    fn effective_request_uri() {
+        let origin_form = RequestPacket::try_from_str("GET /path?q=1 HTTP/1.1\r\nHost: example.com:8080\r\n\r\n").unwrap();
+        assert_eq!(origin_form.effective_request_uri(false), Some("http://example.com:8080/path?q=1".to_string()));
+        assert_eq!(origin_form.effective_request_uri(true), Some("https://example.com:8080/path?q=1".to_string()));
+
+        let absolute_form = RequestPacket::try_from_str("GET https://other.example/a HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        assert_eq!(absolute_form.effective_request_uri(false), Some("https://other.example/a".to_string()));
+
+        let asterisk = RequestPacket::try_from_str("OPTIONS * HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        assert_eq!(asterisk.effective_request_uri(false), Some("http://example.com".to_string()));
+
+        let connect = RequestPacket::try_from_str("CONNECT example.com:443 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(connect.effective_request_uri(true), Some("https://example.com:443".to_string()));
+
+        let no_host = RequestPacket::try_from_str("GET /path HTTP/1.0\r\n\r\n").unwrap();
+        assert_eq!(no_host.effective_request_uri(false), None);
+    }
+
+    #[test]
+    fn header_bool() {
+        for (value, expected) in [
+            ("1", Some(true)),
+            ("true", Some(true)),
+            ("TRUE", Some(true)),
+            ("yes", Some(true)),
+            ("Yes", Some(true)),
+            ("0", Some(false)),
+            ("false", Some(false)),
+            ("False", Some(false)),
+            ("no", Some(false)),
+            ("NO", Some(false)),
+            ("maybe", None),
+            ("2", None),
+        ] {
+            let rp = RequestPacket::try_from_str(&format!("GET / HTTP/1.1\r\nDNT: {value}\r\n\r\n")).unwrap();
+            assert_eq!(rp.header_bool("dnt"), expected, "{value}");
+        }
+
+        let rp = RequestPacket::try_from_str("GET / HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(rp.header_bool("Upgrade-Insecure-Requests"), None);
+
+        let res = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nX-Cache-Hit: yes\r\n\r\n").unwrap();
+        assert_eq!(res.header_bool("X-Cache-Hit"), Some(true));
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn text_decoded() {
+        let mut packet = RequestPacket::try_from_str("POST / HTTP/1.1\r\nContent-Type: text/plain; charset=\"latin1\"\r\n\r\n").unwrap();
+        assert_eq!(packet.text_decoded(), None);
+        packet.body = Some(Body::Bytes(vec![0xc0, b'!']));
+        assert_eq!(packet.text_decoded(), Some("À!".to_string()));
+    }
+
+    #[test]
+    fn cookies() {
+        let req = RequestPacket::try_from_str("GET / HTTP/1.1\r\nCookie: session=abc123; theme=dark; lang=en\r\n\r\n").unwrap();
+        assert_eq!(req.cookies(), vec![
+            ("session".to_string(), "abc123".to_string()),
+            ("theme".to_string(), "dark".to_string()),
+            ("lang".to_string(), "en".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn preferences() {
+        let req = RequestPacket::try_from_str("GET / HTTP/1.1\r\nPrefer: return=minimal, wait=100\r\nPrefer: Respond-Async; x=1, handling=\"lenient\"\r\n\r\n").unwrap();
+        assert_eq!(req.preferences(), vec![
+            ("return".to_string(), Some("minimal".to_string())),
+            ("wait".to_string(), Some("100".to_string())),
+            ("respond-async".to_string(), None),
+            ("handling".to_string(), Some("lenient".to_string())),
+        ]);
+
+        let built = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url("/")
+            .version(Version::V1_1)
+            .preferences(&[("return", Some("representation"))])
+            .preferences(&[("return", Some("minimal")), ("respond-async", None)])
+            .try_build()
+            .unwrap();
+        assert_eq!(built.get_header_all("Prefer"), vec!["return=minimal, respond-async"]);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn decompressed_gzip_body() {
+        use std::io::Write;
+
+        let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        enc.write_all(br#"{"upload": true}"#).unwrap();
+        let compressed: Vec<u8> = enc.finish().unwrap();
+
+        let mut req = RequestPacketBuilder::new()
+            .post()
+            .path("/upload")
+            .http_1_1()
+            .header(("Content-Encoding", "gzip"))
+            .try_build()
+            .unwrap();
+        assert_eq!(req.decompressed_body(), Ok(None));
+
+        req.body = Some(Body::Bytes(compressed));
+        assert_eq!(req.decompressed_body(), Ok(Some(br#"{"upload": true}"#.to_vec())));
+    }
+
+    #[test]
+    fn keep_alive() {
+        let parse = |s: &str| RequestPacket::try_from_str(s).unwrap();
+        assert!(parse("GET / HTTP/1.1\r\n\r\n").keep_alive());
+        assert!(!parse("GET / HTTP/1.1\r\nConnection: close\r\n\r\n").keep_alive());
+        assert!(!parse("GET / HTTP/1.0\r\n\r\n").keep_alive());
+        assert!(parse("GET / HTTP/1.0\r\nConnection: Keep-Alive\r\n\r\n").keep_alive());
+    }
+
+    #[test]
+    fn reject_absolute_form() {
+        let absolute = RequestPacket::try_from_str("GET http://example.com/index.html HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        assert_eq!(absolute.reject_absolute_form(), Err(PacketErr::AbsoluteFormNotAllowed));
+
+        let origin = RequestPacket::try_from_str("GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        assert_eq!(origin.reject_absolute_form(), Ok(()));
+    }
+
+    #[test]
+    fn without_query() {
+        let rp = RequestPacket::try_from_str("GET /a?b=1 HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        let stripped = rp.without_query();
+        assert_eq!(stripped.url, "/a");
+        assert_eq!(stripped.headers, rp.headers);
+        assert_eq!(stripped.without_query().url, "/a");
+    }
+
+    #[test]
+    fn headers_eq_ignore_case() {
+        let req = RequestPacket::try_from_str(
+            "GET / HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\nAccept: text/html\r\n\r\n"
+        ).unwrap();
+        assert!(req.headers_eq_ignore_case(&[
+            Header::new("accept", "text/html"),
+            Header::new("HOST", "example.com"),
+            Header::new("Accept", "*/*"),
+        ]));
+        // values are case-sensitive
+        assert!(!req.headers_eq_ignore_case(&[
+            Header::new("Host", "Example.com"),
+            Header::new("Accept", "*/*"),
+            Header::new("Accept", "text/html"),
+        ]));
+        // repeated headers are counted
+        assert!(!req.headers_eq_ignore_case(&[
+            Header::new("Host", "example.com"),
+            Header::new("Accept", "*/*"),
+        ]));
+    }
+
+    #[test]
+    fn summary() {
+        let req = RequestPacket::try_from_str(
+            "POST /submit?draft=1 HTTP/1.1\r\nHost: example.com\r\nContent-Length: 5\r\n\r\nhello"
+        ).unwrap();
+        assert_eq!(req.summary(), "POST /submit (2 headers, 5 body bytes)");
+
+        let long = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url(&format!("/{}", "é".repeat(100)))
+            .version(Version::V1_1)
+            .try_build()
+            .unwrap();
+        let summary: String = long.summary();
+        assert!(summary.len() <= SUMMARY_MAX_LEN);
+        assert!(summary.starts_with("GET /é"));
+        assert!(summary.ends_with("... (0 headers, 0 body bytes)"));
+    }
+
+    #[test]
+    fn query_params() {
+        let rp = RequestPacket::try_from_str("GET /search?q=rust%20lang&page=2&tag=a&tag=b%26c&flag&&name=J%C3%BCrgen+M HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(rp.path(), "/search");
+        assert_eq!(
+            rp.query_params(),
+            vec![
+                ("q".to_string(), "rust lang".to_string()),
+                ("page".to_string(), "2".to_string()),
+                ("tag".to_string(), "a".to_string()),
+                ("tag".to_string(), "b&c".to_string()),
+                ("flag".to_string(), String::new()),
+                ("name".to_string(), "J\u{fc}rgen M".to_string()),
+            ]
+        );
+
+        let no_query = RequestPacket::try_from_str("GET /plain HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(no_query.path(), "/plain");
+        assert!(no_query.query_params().is_empty());
+
+        // malformed escapes are kept
+        let malformed = RequestPacket::try_from_str("GET /?a=100%&b=%zz HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(
+            malformed.query_params(),
+            vec![("a".to_string(), "100%".to_string()), ("b".to_string(), "%zz".to_string())]
+        );
+    }
+
+    #[test]
+    fn query_param() {
+        let req = RequestPacket::try_from_str("GET /search?q=rust+http&tag=a&tag=b&caf%C3%A9=yes HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(req.query_param("q"), Some("rust http".to_string()));
+        assert_eq!(req.query_param("café"), Some("yes".to_string()));
+        // the first value wins
+        assert_eq!(req.query_param("tag"), Some("a".to_string()));
+        assert_eq!(req.query_param("missing"), None);
+        assert_eq!(req.query_param("Q"), None);
+    }
+
+    #[test]
+    fn canonical_query() {
+        let parse = |url: &str| RequestPacket::try_from_str(&format!("GET {url} HTTP/1.1\r\n\r\n")).unwrap();
+        assert_eq!(parse("/search?b=2&a=1&a=0").canonical_query(), Some("a=0&a=1&b=2".to_string()));
+        assert_eq!(parse("/?q=a+b&flag").canonical_query(), parse("/?flag=&q=a%20b").canonical_query());
+        assert_eq!(parse("/?q=a+b&flag").canonical_query(), Some("flag=&q=a%20b".to_string()));
+        assert_eq!(parse("/?x=%26%3D").canonical_query(), Some("x=%26%3D".to_string()));
+        assert_eq!(parse("/?").canonical_query(), Some(String::new()));
+        assert_eq!(parse("/path").canonical_query(), None);
+    }
+
+    #[test]
+    fn redirect_relative_location() {
+        let req = RequestPacket::try_from_str("GET /docs/guide/intro?x=1 HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+
+        let redirected = req.for_redirect("../api/start", &StatusCode::MovedPermanently);
+        assert_eq!(redirected.url, "/docs/api/start");
+        assert_eq!(redirected.method, Method::Get);
+        assert_eq!(redirected.get_header("Host"), Some("example.com"));
+
+        assert_eq!(req.for_redirect("next", &StatusCode::Found).url, "/docs/guide/next");
+        assert_eq!(req.for_redirect("/login", &StatusCode::Found).url, "/login");
+        assert_eq!(req.for_redirect("?page=2", &StatusCode::Found).url, "/docs/guide/intro?page=2");
+        assert_eq!(
+            req.for_redirect("https://other.example/", &StatusCode::Found).url,
+            "https://other.example/"
+        );
+
+        let absolute = RequestPacket::try_from_str("GET http://example.com/a/b HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(absolute.for_redirect("c", &StatusCode::Found).url, "http://example.com/a/c");
+        assert_eq!(absolute.for_redirect("/d", &StatusCode::Found).url, "http://example.com/d");
+        assert_eq!(absolute.for_redirect("//cdn.example/e", &StatusCode::Found).url, "http://cdn.example/e");
+    }
+
+    #[test]
+    fn redirect_303_switches_to_get() {
+        let req = RequestPacket::try_from_str(
+            "POST /form HTTP/1.1\r\nHost: example.com\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\nhello"
+        ).unwrap();
+
+        let redirected = req.for_redirect("/done", &StatusCode::SeeOther);
+        assert_eq!(redirected.method, Method::Get);
+        assert_eq!(redirected.url, "/done");
+        assert_eq!(redirected.body, None);
+        assert_eq!(redirected.header_order(), vec!["host".to_string()]);
+
+        // 307 keeps the method and the body
+        let kept = req.for_redirect("/done", &StatusCode::TemporaryRedirect);
+        assert_eq!(kept.method, Method::Post);
+        assert_eq!(kept.body, req.body);
+    }
+
+    #[test]
+    fn redirect_cross_origin_drops_credentials() {
+        let req = RequestPacket::try_from_str(
+            "GET /a HTTP/1.1\r\nHost: example.com\r\nAuthorization: Bearer x\r\nCookie: id=1\r\nProxy-Authorization: Basic y\r\nAccept: */*\r\n\r\n"
+        ).unwrap();
+
+        let redirected = req.for_redirect("https://evil.example/b", &StatusCode::Found);
+        assert_eq!(redirected.url, "https://evil.example/b");
+        assert_eq!(redirected.get_header("Host"), Some("evil.example"));
+        assert_eq!(redirected.header_order(), vec!["host".to_string(), "accept".to_string()]);
+
+        // same host, or a relative location, keeps them
+        let same = req.for_redirect("http://example.com/c", &StatusCode::Found);
+        assert_eq!(same.get_header("Authorization"), Some("Bearer x"));
+        assert_eq!(req.for_redirect("/c", &StatusCode::Found).get_header("Cookie"), Some("id=1"));
+
+        // absolute-form requests compare the scheme too
+        let absolute = RequestPacket::try_from_str(
+            "GET https://example.com/a HTTP/1.1\r\nHost: example.com\r\nAuthorization: Bearer x\r\n\r\n"
+        ).unwrap();
+        assert_eq!(absolute.for_redirect("/b", &StatusCode::Found).get_header("Authorization"), Some("Bearer x"));
+        assert_eq!(absolute.for_redirect("http://example.com/b", &StatusCode::Found).get_header("Authorization"), None);
+        let other = absolute.for_redirect("//cdn.example/b", &StatusCode::Found);
+        assert_eq!(other.get_header("Authorization"), None);
+        assert_eq!(other.get_header("Host"), Some("cdn.example"));
+    }
+
+    #[test]
+    fn cors_preflight() {
+        let preflight = RequestPacket::try_from_str(
+            "OPTIONS /api HTTP/1.1\r\nOrigin: https://example.com\r\nAccess-Control-Request-Method: POST\r\n\r\n"
+        ).unwrap();
+        assert!(preflight.is_cors_preflight());
+        assert_eq!(preflight.origin(), Some("https://example.com"));
+
+        // no Access-Control-Request-Method
+        let plain_options = RequestPacket::try_from_str(
+            "OPTIONS /api HTTP/1.1\r\nOrigin: https://example.com\r\n\r\n"
+        ).unwrap();
+        assert!(!plain_options.is_cors_preflight());
+
+        // not OPTIONS
+        let get = RequestPacket::try_from_str(
+            "GET /api HTTP/1.1\r\nOrigin: https://example.com\r\nAccess-Control-Request-Method: POST\r\n\r\n"
+        ).unwrap();
+        assert!(!get.is_cors_preflight());
+    }
+
+    #[test]
+    fn accepted_encodings() {
+        let req = RequestPacket::try_from_str(
+            "GET / HTTP/1.1\r\nAccept-Encoding: gzip;q=1.0, identity; q=0.5, *;q=0\r\nAccept-Encoding: BR\r\n\r\n"
+        ).unwrap();
+        assert_eq!(req.accepted_encodings(), vec![
+            ("gzip".to_string(), 1.0),
+            ("identity".to_string(), 0.5),
+            ("*".to_string(), 0.0),
+            ("br".to_string(), 1.0),
+        ]);
+
+        assert!(req.prefers_encoding("gzip"));
+        assert!(req.prefers_encoding("br"));
+        assert!(req.prefers_encoding("identity"));
+        // excluded by *;q=0
+        assert!(!req.prefers_encoding("deflate"));
+    }
+
+    #[test]
+    fn prefers_encoding_wildcard_and_rejection() {
+        let req = RequestPacket::try_from_str(
+            "GET / HTTP/1.1\r\nAccept-Encoding: gzip;q=0, *;q=0.3\r\n\r\n"
+        ).unwrap();
+        assert!(!req.prefers_encoding("gzip"));
+        assert!(req.prefers_encoding("deflate"));
+        assert!(req.prefers_encoding("identity"));
+
+        let only_gzip = RequestPacket::try_from_str("GET / HTTP/1.1\r\nAccept-Encoding: gzip\r\n\r\n").unwrap();
+        assert!(!only_gzip.prefers_encoding("br"));
+        assert!(only_gzip.prefers_encoding("identity"));
+
+        let no_header = RequestPacket::try_from_str("GET / HTTP/1.1\r\n\r\n").unwrap();
+        assert!(no_header.accepted_encodings().is_empty());
+        assert!(no_header.prefers_encoding("br"));
+    }
+
+    #[test]
+    fn accepted_encodings_invalid_q() {
+        let req = RequestPacket::try_from_str(
+            "GET / HTTP/1.1\r\nAccept-Encoding: gzip;q=abc, br;q=2, deflate\r\n\r\n"
+        ).unwrap();
+        assert_eq!(req.accepted_encodings(), vec![("deflate".to_string(), 1.0)]);
+    }
+
+    #[test]
+    fn transfer_encodings_valid() {
+        let rp = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/")
+            .version(Version::V1_1)
+            .header(("Transfer-Encoding", "gzip, Chunked"))
+            .try_build()
+            .unwrap();
+
+        assert_eq!(rp.transfer_encodings(), vec!["gzip".to_string(), "chunked".to_string()]);
+        assert_eq!(rp.validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_request_target() {
+        let mut rp = RequestPacket::try_from_str("GET /index.html HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(rp.validate(), Ok(()));
+
+        rp.url = "/index.html ".to_string();
+        assert_eq!(rp.validate(), Err(PacketErr::InvalidRequestTarget));
+        rp.url = "\t/index.html".to_string();
+        assert_eq!(rp.validate(), Err(PacketErr::InvalidRequestTarget));
+        rp.url = "/index\u{0}.html".to_string();
+        assert_eq!(rp.validate(), Err(PacketErr::InvalidRequestTarget));
+        rp.url = "/a b".to_string();
+        assert_eq!(rp.validate(), Err(PacketErr::InvalidRequestTarget));
+        rp.url = "/index.html\u{a0}".to_string();
+        assert_eq!(rp.validate(), Err(PacketErr::InvalidRequestTarget));
+    }
+
+    #[test]
+    fn transfer_encodings_chunked_not_last() {
+        let rp = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/")
+            .version(Version::V1_1)
+            .header(("Transfer-Encoding", "chunked"))
+            .header(("Transfer-Encoding", "gzip"))
+            .try_build()
+            .unwrap();
+
+        assert_eq!(rp.transfer_encodings(), vec!["chunked".to_string(), "gzip".to_string()]);
+        assert_eq!(rp.validate(), Err(PacketErr::InvalidTransferEncoding));
+    }
+
+    #[test]
+    fn unframed_chunked_body() {
+        let builder = || RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/")
+            .version(Version::V1_1)
+            .header(("Transfer-Encoding", "chunked"));
+
+        // a plain body
+        let plain = builder().body("hello").try_build().unwrap();
+        assert_eq!(plain.validate(), Err(PacketErr::UnframedChunkedBody));
+
+        // framed, but missing the terminating chunk
+        let unterminated = builder().body("5\r\nhello\r\n").try_build().unwrap();
+        assert_eq!(unterminated.validate(), Err(PacketErr::UnframedChunkedBody));
+
+        let framed = builder().body("5\r\nhello\r\n0\r\n\r\n").try_build().unwrap();
+        assert_eq!(framed.validate(), Ok(()));
+
+        let response = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .chunked_body(&["hello"])
+            .try_build()
+            .unwrap();
+        assert_eq!(response.validate(), Ok(()));
+    }
 }
 
 /// An HTTP response packet.
@@ -345,69 +2059,293 @@ mod request_packet_test {
 ///
 /// That being said, proper value checks have been implemented, so you cannot convert a ResponsePacket into a String with `try_to_string()` when one of the required values for the specified HTTP version is lacking.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ResponsePacket {
     pub version: Version,
     pub status: Option<StatusCode>,
+    /// A reason phrase sent instead of the canonical one of `status`, e.g. `Okay Dokay` in `200 Okay Dokay`.
+    /// The phrase of a [`StatusCode::Custom`] is part of the status itself.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub reason: Option<String>,
     pub headers: Option<Vec<Header>>,
     pub body: Option<Body>,
 }
 
 
 impl ResponsePacket {
-    /// Try to convert resposne packet into a string. Fallible because of varying requirements for different versions.
+    /// Try to convert resposne packet into a string. Fallible because of varying requirements for different versions,
+    /// and because a binary body can't be represented as a string ([`PacketErr::NonUtf8Body`]).
     pub fn try_to_string(&self) -> Result<String, PacketErr> {
+        let mut acc: String = self.try_head_to_string()?;
+        if let Some(b) = self.body.as_ref() {
+            acc.push_str(b.as_str().ok_or(PacketErr::NonUtf8Body)?);
+        }
+        Ok(acc)
+    }
+
+    /// Try to convert resposne packet into bytes. Same as [`ResponsePacket::try_to_string`], but works for binary bodies as well.
+    pub fn try_to_bytes(&self) -> Result<Vec<u8>, PacketErr> {
+        let mut acc: Vec<u8> = self.try_head_to_string()?.into_bytes();
+        if let Some(b) = self.body.as_ref() {
+            acc.extend_from_slice(b.as_bytes());
+        }
+        Ok(acc)
+    }
+
+    /// Everything that comes before the body: the status line, the headers and the blank line ending them
+    fn try_head_to_string(&self) -> Result<String, PacketErr> {
         // Normally, if we are using a builder, if we create a ResponsePacket struct, we can be sure that it has all the required fields. But it doens't hurt to check again
         match self.version {
             Version::V0_9 => {
                 // Disregards everything but the body
                 // Required fields:
                 // 1) Body
-                if let None = self.body {
+                if self.body.is_none() {
                     return Err(PacketErr::NoBody)
                 }
-                Ok(format!(
-                    "{}", self.body.as_ref().unwrap().0
-                ))
+                Ok(String::new())
             }
-            Version::V1_0 => {
-                // Required fields:
+            Version::V1_0 | Version::V1_1 | Version::V2_0 => {
+                // Required fields (the same for all three)
                 // 1) StatusCode
-                if let None = self.status {
+                if self.status.is_none() {
                     return Err(PacketErr::NoStatusCode);
                 }
                 let mut acc = String::new();
-                acc.push_str(format!("{} {}\r\n", self.version, self.status.as_ref().unwrap()).as_str());
+                let status: &StatusCode = self.status.as_ref().unwrap();
+                match &self.reason {
+                    Some(reason) => acc.push_str(format!("{} {} {reason}\r\n", self.version, status.as_int()).as_str()),
+                    None => acc.push_str(format!("{} {status}\r\n", self.version).as_str()),
+                }
                 if let Some(hdrs) = &self.headers {
                     for hdr in hdrs {
                         acc.push_str(format!("{hdr}\r\n").as_str());
                     }
-                    acc.push_str("\r\n");
                 } 
-                if let Some(b) = self.body.as_ref() {
-                    acc.push_str(b.0.as_str());
-                }
+                // End of headers, present even if there are none
+                acc.push_str("\r\n");
                 Ok(acc)
             }
-            Version::V1_1 => {
-                // Required fields (similar to 1.0)
-                // 1) StatusCode
-                if let None = self.status {
-                    return Err(PacketErr::NoStatusCode);
-                }
-                let mut acc = String::new();
-                acc.push_str(format!("{} {}\r\n", self.version, self.status.as_ref().unwrap()).as_str());
-                if let Some(hdrs) = &self.headers {
-                    for hdr in hdrs {
-                        acc.push_str(format!("{hdr}\r\n").as_str());
-                    }
-                    acc.push_str("\r\n");
-                } 
-                if let Some(b) = self.body.as_ref() {
-                    acc.push_str(b.0.as_str());
+        }   
+    }
+}
+
+impl ResponsePacket {
+    /// Try to parse a finished response packet from a string. Fallible.
+    ///
+    /// Shorthand for [`ResponsePacketBuilder::try_from_str`] followed by [`ResponsePacketBuilder::try_build`].
+    /// Like the builder, this does **not** parse HTTP/0.9 responses.
+    pub fn try_from_str(s: &str) -> Result<ResponsePacket, PacketErr> {
+        ResponsePacketBuilder::try_from_str(s)?.try_build()
+    }
+
+    /// Same as [`ResponsePacket::try_from_str`], but also returns the number of bytes of `s` the packet occupied on the wire
+    /// (status line, headers and body), as written by the sender.
+    pub fn try_from_str_with_wire_size(s: &str) -> Result<(ResponsePacket, usize), PacketErr> {
+        let (builder, wire_size) = ResponsePacketBuilder::parse(s)?;
+        Ok((builder.try_build()?, wire_size))
+    }
+
+    /// The headers as a slice, empty if there are none
+    fn header_list(&self) -> &[Header] {
+        self.headers.as_deref().unwrap_or(&[])
+    }
+
+    /// The trimmed value of the first header named `name`. Names are compared case-insensitively.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        header_values(self.header_list(), name).next()
+    }
+
+    /// The trimmed values of every header named `name`, in order of appearance. Names are compared case-insensitively.
+    pub fn get_header_all(&self, name: &str) -> Vec<&str> {
+        header_values(self.header_list(), name).collect()
+    }
+
+    /// The value of the `X-Request-Id` header, if any
+    pub fn request_id(&self) -> Option<&str> {
+        self.get_header("X-Request-Id")
+    }
+
+    /// The value of the first header named `key` as a boolean, for headers like `DNT` or `Upgrade-Insecure-Requests`.
+    ///
+    /// `1`/`true`/`yes` and `0`/`false`/`no` are recognized, case-insensitively. `None` if the header is missing or has another value.
+    pub fn header_bool(&self, key: &str) -> Option<bool> {
+        self.get_header(key).and_then(parse_bool)
+    }
+
+    /// The codings listed in the `Transfer-Encoding` header(s), in the order they were applied
+    pub fn transfer_encodings(&self) -> Vec<String> {
+        transfer_encodings(self.header_list())
+    }
+
+    /// Check the packet for semantic errors that parsing and building do not catch,
+    /// such as `chunked` not being the final transfer coding, or a `chunked` body without chunk framing.
+    pub fn validate(&self) -> Result<(), PacketErr> {
+        validate_message(self.header_list(), self.body.as_ref())
+    }
+
+    /// Whether the packet has the same headers as `other`, ignoring their order and the case of the names.
+    /// Repeated headers must appear the same number of times in both. A packet without headers equals an empty slice.
+    pub fn headers_eq_ignore_case(&self, other: &[Header]) -> bool {
+        headers_eq_ignore_case(self.header_list(), other)
+    }
+
+    /// Every link from the `Link` header(s), e.g. the `next` and `prev` pages of a paginated API
+    pub fn links(&self) -> Vec<Link> {
+        header_values(self.header_list(), "Link")
+            .flat_map(Link::parse_header)
+            .collect()
+    }
+
+    /// Every cookie set by the `Set-Cookie` header(s). These headers are not merged, so each one holds a single cookie.
+    /// Malformed ones are skipped.
+    pub fn set_cookies(&self) -> Vec<Cookie> {
+        header_values(self.header_list(), "Set-Cookie")
+            .filter_map(Cookie::parse_set_cookie)
+            .collect()
+    }
+
+    /// Whether the connection has to be closed after this response: with `Connection: close`, or when there is a body
+    /// but neither `Content-Length` nor chunked `Transfer-Encoding`, so that only closing the connection marks the end of the body.
+    pub fn must_close(&self) -> bool {
+        let headers: &[Header] = self.header_list();
+        if codings(headers, "Connection").iter().any(|c| c == "close") {
+            return true;
+        }
+        let chunked: bool = transfer_encodings(headers).last().is_some_and(|c| c == "chunked");
+        let has_length: bool = header_values(headers, "Content-Length").next().is_some();
+        self.body.is_some() && !chunked && !has_length
+    }
+
+    /// Whether the server advertises range request support with `Accept-Ranges`, e.g. `Accept-Ranges: bytes`.
+    ///
+    /// `Accept-Ranges: none` and a missing header both mean no support.
+    pub fn accepts_ranges(&self) -> bool {
+        self.accept_ranges_unit().is_some_and(|unit| !unit.eq_ignore_ascii_case("none"))
+    }
+
+    /// The range unit from the `Accept-Ranges` header, e.g. `bytes` or `none`. `None` if the header is missing or empty.
+    pub fn accept_ranges_unit(&self) -> Option<String> {
+        self.get_header("Accept-Ranges")
+            .filter(|unit| !unit.is_empty())
+            .map(|unit| unit.to_string())
+    }
+
+    /// The `Expires` header in seconds since the epoch.
+    ///
+    /// The invalid but common `Expires: 0` ("already expired") gives `0`. `None` if the header is missing or not a valid HTTP-date.
+    pub fn expires(&self) -> Option<u64> {
+        match self.get_header("Expires")? {
+            "0" => Some(0),
+            value => parse_http_date(value),
+        }
+    }
+
+    /// Whether a cached copy of this response is still fresh (RFC 9111 4.2), so it can be reused without revalidation.
+    ///
+    /// `response_time_secs` is when the response was received and `now_secs` is the current time, both in seconds since the epoch.
+    /// The freshness lifetime is taken from `Cache-Control: max-age`, or else from `Expires` minus `Date`.
+    /// The age accounts for the `Date` and `Age` headers plus the time spent in the cache.
+    /// Responses with `Cache-Control: no-cache` or without explicit freshness information are never fresh.
+    pub fn is_fresh(&self, response_time_secs: u64, now_secs: u64) -> bool {
+        let directives: Vec<String> = codings(self.header_list(), "Cache-Control");
+        if directives.iter().any(|d| d == "no-cache") {
+            return false;
+        }
+
+        let date: Option<u64> = self.get_header("Date").and_then(parse_http_date);
+        let max_age: Option<u64> = directives
+            .iter()
+            .find_map(|d| d.strip_prefix("max-age="))
+            .and_then(|v| v.trim_matches('"').parse().ok());
+        let lifetime: u64 = match max_age {
+            Some(max_age) => max_age,
+            None => {
+                if self.get_header("Expires").is_none() {
+                    return false;
                 }
-                Ok(acc)
+                // any invalid `Expires` means already expired
+                let expires: u64 = self.expires().unwrap_or(0);
+                expires.saturating_sub(date.unwrap_or(response_time_secs))
             }
-        }   
+        };
+
+        let apparent_age: u64 = date.map_or(0, |date| response_time_secs.saturating_sub(date));
+        let age_header: u64 = self.get_header("Age").and_then(|age| age.parse().ok()).unwrap_or(0);
+        let current_age: u64 = apparent_age.max(age_header) + now_secs.saturating_sub(response_time_secs);
+
+        lifetime > current_age
+    }
+
+    /// The body decoded in the charset from the `Content-Type` header (e.g. `text/html; charset=ISO-8859-1`), falling back to UTF-8.
+    /// `encoding` feature needed.
+    ///
+    /// `None` if there is no body, or if it is not valid in that charset.
+    #[cfg(feature = "encoding")]
+    pub fn text_decoded(&self) -> Option<String> {
+        decode_text(self.header_list(), self.body.as_ref())
+    }
+
+    /// The body with the `Content-Encoding` codings undone, or `None` if there is no body. `compression` feature needed.
+    ///
+    /// Multiple codings (e.g. `Content-Encoding: gzip, br`) are decoded in reverse order.
+    #[cfg(feature = "compression")]
+    pub fn decompressed_body(&self) -> Result<Option<Vec<u8>>, PacketErr> {
+        decompress_body(self.header_list(), self.body.as_ref())
+    }
+
+    /// Build a response to `req` with its version, the given status and body, and a `Content-Length` header.
+    ///
+    /// For HTTP/1.x, a `Connection: keep-alive` or `Connection: close` header is added following [`RequestPacket::keep_alive`].
+    /// The body is left out for `HEAD` requests, and together with `Content-Length` for statuses that don't allow one (e.g. `204`).
+    /// An HTTP/0.9 response is just the body.
+    pub fn respond_to(req: &RequestPacket, status: StatusCode, body: impl Into<Body>) -> ResponsePacket {
+        let body: Body = body.into();
+        if req.version == Version::V0_9 {
+            return ResponsePacket { version: Version::V0_9, status: None, reason: None, headers: None, body: Some(body) };
+        }
+
+        let mut headers: Vec<Header> = Vec::new();
+        let body: Option<Body> = if status.allows_body() {
+            headers.push(Header::new("Content-Length", body.len().to_string()));
+            Some(body).filter(|b| !b.is_empty() && req.method != Method::Head)
+        } else {
+            None
+        };
+        if matches!(req.version, Version::V1_0 | Version::V1_1) {
+            let connection: &str = if req.keep_alive() { "keep-alive" } else { "close" };
+            headers.push(Header::new("Connection", connection));
+        }
+
+        ResponsePacket {
+            version: req.version,
+            status: Some(status),
+            reason: None,
+            headers: Some(headers),
+            body,
+        }
+    }
+
+    /// Build a response to an `OPTIONS` request advertising the allowed methods in the `Allow` header.
+    ///
+    /// Uses `200 OK` rather than `204 No Content`, since `Content-Length: 0` must not be sent with a 204.
+    pub fn options_response(version: Version, allowed: &[Method]) -> ResponsePacket {
+        let allow: String = allowed
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        ResponsePacket {
+            version,
+            status: Some(StatusCode::Ok),
+            reason: None,
+            headers: Some(vec![
+                Header::new("Allow", allow),
+                Header::new("Content-Length", "0"),
+            ]),
+            body: None,
+        }
     }
 }
 
@@ -423,12 +2361,7 @@ impl TryInto<Vec<u8>> for ResponsePacket {
     type Error = PacketErr;
 
     fn try_into(self) -> Result<Vec<u8>, Self::Error> {
-        match self.try_to_string() {
-            Ok(s) => {
-                Ok(s.into_bytes())
-            }
-            Err(e) => Err(e)
-        }
+        self.try_to_bytes()
     }
 }
 
@@ -440,7 +2373,11 @@ pub struct ResponsePacketBuilder {
     pub version: Option<Version>,
     pub status: Option<StatusCode>,
     pub headers: Option<Vec<Header>>,
-    pub body: Option<Body>
+    pub body: Option<Body>,
+    /// Set with `status_with_reason()`
+    reason: Option<String>,
+    /// Set with `auto_content_length()`
+    auto_content_length: bool,
 }
 
 impl ResponsePacketBuilder {
@@ -451,9 +2388,26 @@ impl ResponsePacketBuilder {
     /// Status setter
     pub fn status(mut self, status: StatusCode) -> Self {
         self.status = Some(status);
+        self.reason = None;
         self
     }
 
+    /// Status setter with a custom reason phrase, e.g. `status_with_reason(200, "Okay Dokay")` for `HTTP/1.1 200 Okay Dokay`.
+    ///
+    /// The status stays the canonical one, e.g. `StatusCode::Ok` for `200`, and the phrase is kept in [`ResponsePacket::reason`].
+    /// For codes without a variant, it is the phrase of the [`StatusCode::Custom`] instead.
+    ///
+    /// Fails with [`PacketErr::InvalidStatusCode`] if `code` is outside of `100` to `599`.
+    pub fn status_with_reason(mut self, code: StatusCodeInt, reason: &str) -> Result<Self, PacketErr> {
+        let (status, reason) = match StatusCode::try_from(code)? {
+            StatusCode::Custom(code, _) => (StatusCode::Custom(code, reason.to_string()), None),
+            known => (known, Some(reason.to_string())),
+        };
+        self.status = Some(status);
+        self.reason = reason;
+        Ok(self)
+    }
+
     /// Header setter. Instantiates the header list or extends it.
     pub fn headers(mut self, headers: Vec<Header>) -> Self {
         match self.headers {
@@ -468,10 +2422,7 @@ impl ResponsePacketBuilder {
     /// Header setter. Intantiates the list or adds a new header to it.
     pub fn header<T>(mut self, header_pair: (T, T)) -> Self
     where T: Into<String> {
-        let h = Header {
-            key: header_pair.0.into(),
-            value: header_pair.1.into()
-        };
+        let h = Header::new(header_pair.0, header_pair.1);
         match self.headers {
             Some(ref mut hdrs) => {
                 hdrs.push(h);
@@ -484,24 +2435,118 @@ impl ResponsePacketBuilder {
         self
     }
 
+    /// Header setter that replaces every existing header of the same name (compared case-insensitively), or adds it if there is none.
+    ///
+    /// Use this for headers that must be unique, like `Content-Length` or `Content-Type`.
+    pub fn set_header<T>(mut self, header_pair: (T, T)) -> Self
+    where T: Into<String> {
+        set_header(&mut self.headers, Header::new(header_pair.0, header_pair.1));
+        self
+    }
+
+    /// Removes every header named `name` (compared case-insensitively). Does nothing if there is none.
+    pub fn remove_header(mut self, name: &str) -> Self {
+        if let Some(ref mut hdrs) = self.headers {
+            hdrs.retain(|h| !h.key.trim().eq_ignore_ascii_case(name));
+        }
+        self
+    }
+
+    /// Whether a header named `name` (compared case-insensitively) has been added
+    pub fn has_header(&self, name: &str) -> bool {
+        self.headers.as_deref().is_some_and(|hdrs| header_values(hdrs, name).next().is_some())
+    }
+
     /// Version setter
     pub fn version(mut self, version: Version) -> Self {
         self.version = Some(version);
         self
     }
 
+    /// Sets the version to the one of the request being replied to.
+    ///
+    /// An HTTP/0.9 request yields an HTTP/0.9 response, which is serialized as the bare body (no status line or headers), so a body should be set.
+    pub fn matching_request(mut self, req: &RequestPacket) -> Self {
+        self.version = Some(req.version);
+        self
+    }
+
     /// Body setter
     pub fn body<T>(mut self, body: T) -> Self
     where T: std::fmt::Display {
-        self.body = Some(Body(format!("{body}")));
+        self.body = Some(Body::Text(format!("{body}")));
+        self
+    }
+
+    /// Sets `Transfer-Encoding: chunked` and a body made of `chunks`, each framed with its length in hex, followed by the terminating `0\r\n\r\n`.
+    ///
+    /// Empty chunks are skipped, since a zero-length chunk would end the body early.
+    pub fn chunked_body(mut self, chunks: &[&str]) -> Self {
+        let mut framed = String::new();
+        for chunk in chunks.iter().filter(|c| !c.is_empty()) {
+            framed.push_str(&format!("{:x}\r\n{chunk}\r\n", chunk.len()));
+        }
+        framed.push_str("0\r\n\r\n");
+        self = self.header(("Transfer-Encoding", "chunked"));
+        self.body = Some(Body::Text(framed));
+        self
+    }
+
+    /// Sets a strong `ETag` header, e.g. `ETag: "value"`. Quotes around `value` are not doubled.
+    pub fn strong_etag(self, value: &str) -> Self {
+        let etag: String = format!("\"{}\"", value.trim_matches('"'));
+        self.header(("ETag", etag.as_str()))
+    }
+
+    /// Sets a weak `ETag` header, e.g. `ETag: W/"value"`. Quotes around `value` are not doubled.
+    pub fn weak_etag(self, value: &str) -> Self {
+        let etag: String = format!("W/\"{}\"", value.trim_matches('"'));
+        self.header(("ETag", etag.as_str()))
+    }
+
+    /// Sets the CORS headers `Access-Control-Allow-Origin`, `Access-Control-Allow-Methods` and `Access-Control-Allow-Headers`.
+    ///
+    /// The methods and headers are skipped if the respective list is empty.
+    pub fn cors(mut self, allow_origin: &str, allow_methods: &[Method], allow_headers: &[&str]) -> Self {
+        self = self.header(("Access-Control-Allow-Origin", allow_origin));
+        if !allow_methods.is_empty() {
+            let methods: String = allow_methods
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            self = self.header(("Access-Control-Allow-Methods", methods.as_str()));
+        }
+        if !allow_headers.is_empty() {
+            self = self.header(("Access-Control-Allow-Headers", allow_headers.join(", ").as_str()));
+        }
         self
     }
 
-    /// Sets the `Content-Length` header. If there is no body, does not set anything
+    /// Serializes `value` to JSON and sets it as the body, along with `Content-Type: application/json` and `Content-Length`.
+    /// `serde` and `json` features needed.
+    ///
+    /// Fails with [`PacketErr::JsonSerializationFailed`] if `value` can't be serialized, e.g. a map with non-string keys.
+    #[cfg(all(feature = "serde", feature = "json"))]
+    pub fn json_body<T: serde::Serialize>(mut self, value: &T) -> Result<Self, PacketErr> {
+        let json: String = serde_json::to_string(value).map_err(|e| PacketErr::JsonSerializationFailed(e.to_string()))?;
+        let len: String = json.len().to_string();
+        self.body = Some(Body::Text(json));
+        Ok(self
+            .set_header(("Content-Type", "application/json"))
+            .set_header(("Content-Length", len.as_str())))
+    }
+
+    /// Sets the `X-Request-Id` header, used to correlate requests and responses in logs and traces
+    pub fn request_id(self, id: &str) -> Self {
+        self.header(("X-Request-Id", id))
+    }
+
+    /// Sets the `Content-Length` header to the byte length of the current body. If there is no body, does not set anything
     pub fn content_length(mut self) -> Self {
         match self.body {
             Some(ref body) => {
-                let byte_count: usize = body.0.len();
+                let byte_count: usize = body.len();
                 self = self.header(("Content-Length", format!("{byte_count}").as_str()));
                 self
             }
@@ -511,9 +2556,36 @@ impl ResponsePacketBuilder {
         }
     }
 
+    /// Let `try_build` set the `Content-Length` header from the final body, unless a `Content-Length` header has been set explicitly
+    /// or the body is sent `chunked`, since a message with both would have ambiguous framing
+    pub fn auto_content_length(mut self) -> Self {
+        self.auto_content_length = true;
+        self
+    }
+
+    /// Same as [`ResponsePacketBuilder::try_build`], but also fails with [`PacketErr::BodyNotAllowedForStatus`]
+    /// if a body is set for a `1xx`, `204 No Content` or `304 Not Modified` status.
+    pub fn try_build_strict(self) -> Result<ResponsePacket, PacketErr> {
+        if let Some(status) = &self.status
+            && self.body.is_some()
+            && !status.allows_body()
+        {
+            return Err(PacketErr::BodyNotAllowedForStatus(status.as_int()));
+        }
+        self.try_build()
+    }
+
     pub fn try_build(mut self) -> Result<ResponsePacket, PacketErr> {
         // required fields
-        if let None = self.version { return Err(PacketErr::NoVersionFound) };
+        if self.version.is_none() { return Err(PacketErr::NoVersionFound) };
+
+        // Inject `Content-Length` if requested and not set explicitly, and the body is not framed by `chunked`
+        if self.auto_content_length
+            && self.body.is_some()
+            && !self.headers.iter().flatten().any(|h| h.key.trim().eq_ignore_ascii_case("Content-Length"))
+            && transfer_encodings(self.headers.as_deref().unwrap_or(&[])).last().is_none_or(|c| c != "chunked") {
+            self = self.content_length();
+        }
 
         let res: ResponsePacket = match self.version.unwrap() {
             Version::V0_9 => {
@@ -523,6 +2595,7 @@ impl ResponsePacketBuilder {
                     version: self.version.unwrap(),
                     body: self.body,
                     status: self.status,
+                    reason: self.reason,
                     headers: self.headers,
                 }
             },
@@ -535,24 +2608,28 @@ impl ResponsePacketBuilder {
                 // 
                 // <html><body>Hello, world!</body></html>
                 // ```
-                if let None = self.status {
+                if self.status.is_none() {
                     return Err(PacketErr::NoStatusCode);
                 }
                 ResponsePacket {
                     version: self.version.unwrap(),
                     status: Some(self.status.unwrap()),
+                    reason: self.reason,
                     body: self.body,
                     headers: self.headers,
                 }
             },
-            Version::V1_1 => {
-                // Pretty much the same structure as for HTTP/1.1
-                if let None = self.status {
+            Version::V1_1 | Version::V2_0 => {
+                // Pretty much the same structure as for HTTP/1.0
+                // HTTP/2.0 is treated as textual HTTP/1.1 with a different version string,
+                // so it has the same required fields
+                if self.status.is_none() {
                     return Err(PacketErr::NoStatusCode);
                 }
                 ResponsePacket {
                     version: self.version.unwrap(),
                     status: Some(self.status.unwrap()),
+                    reason: self.reason,
                     body: self.body,
                     headers: self.headers,
                 }
@@ -561,6 +2638,12 @@ impl ResponsePacketBuilder {
         Ok(res)
     }
 
+    /// Same as [`ResponsePacketBuilder::try_from_str`], but also accepts bare `\n` line endings in the status line and headers.
+    /// The body is not changed.
+    pub fn try_from_str_lenient(s: &str) -> Result<Self, PacketErr> {
+        Self::try_from_str(&normalize_head_line_endings(s))
+    }
+
     /// Try to parse a HTTP response packet from a string.
     ///
     /// **IMPORTANT NOTE**: HTTP/0.9 packets only consist of the body, so they are pretty much unparsable. Any string is a valid HTTP/0.9 packet. Therefore, **this does NOT parse HTTP/0.9 packets**.
@@ -570,24 +2653,30 @@ impl ResponsePacketBuilder {
     /// <p>That's it</p>
     /// ```
     pub fn try_from_str(s: &str) -> Result<Self, PacketErr> {
-        if s.trim().len() == 0 {
+        Self::parse(s).map(|(builder, _)| builder)
+    }
+
+    /// Parse a response, and also return the number of bytes of `s` it occupied (status line, headers and body)
+    fn parse(s: &str) -> Result<(Self, usize), PacketErr> {
+        if s.trim().is_empty() {
             return Err(PacketErr::InvalidLines);
         }
 
-        let mut lines: Vec<&str> = s.split("\r\n").collect();
-        if lines.len() == 1 || lines.len() == 2 {
+        let lines: Vec<&str> = s.split("\r\n").collect();
+        if lines.len() < 3 {
             // Only one \r\n sequence found, or none at all
-            // At least two are expected (After the headers
+            // At least two are expected: one ending the status line and one ending the headers.
+            // The shortest valid packet has no headers and splits into exactly 3 lines
             // e.g.
             // ```
-            // HTTP/1.0 200 OK\r\nHeader1: Value1\r\n\r\n
+            // HTTP/1.0 204 No Content\r\n\r\n
             // ```
             return Err(PacketErr::InvalidLines);
         }
 
         // check if the status line (the first line) starts with a supported HTTP version
         // Do not account for HTTP/0.9
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
         let first_line = lines[0];
 
         // get the version
@@ -597,6 +2686,12 @@ impl ResponsePacketBuilder {
         // get the status code from the first line
         let code_res: Result<StatusCode, PacketErr> = StatusCode::try_from_first_res_line(first_line);
         let code = code_res?;
+        // keep a reason phrase that differs from the canonical one, so that the status line serializes back the same
+        let phrase: String = first_line.split_whitespace().skip(2).collect::<Vec<&str>>().join(" ");
+        let reason: Option<String> = match &code {
+            StatusCode::Custom(..) => None,
+            known => Some(phrase).filter(|phrase| *phrase != known.description()),
+        };
 
         // if there is no "" in the lines list, then that means that no \r\n\r\n sequnce was found
         // this is invalid
@@ -610,7 +2705,7 @@ impl ResponsePacketBuilder {
             if index == 0 {
                 continue;
             }
-            if *line == "" {
+            if line.is_empty() {
                 // we hit the end of the headers
                 break;
             }
@@ -618,43 +2713,1161 @@ impl ResponsePacketBuilder {
                 Ok(h) => {
                     headers.push(h);
                 }
-                Err(e) => { return Err(e); }
+                Err(e) => { return Err(with_line_number(e, index + 1)); }
             }
         }
         
         // now that we parsed the headers, parse the body
         let index_header_end: usize = lines
             .iter()
-            .position(|x| *x == "")
+            .position(|x| x.is_empty())
             .expect("Internal Error: Could not find `\"\"` in the list of lines");
-        let body_start_index = index_header_end + 1;
-        // remove all the lines before this one
-        // (inclusive exclusive)
-        lines = lines.drain(0..body_start_index).collect();
-        let body_str = lines.join("\r\n");
-        let body: Option<Body> = match body_str.as_str() {
-            "" => None,
-            s => Some(Body(s.to_string()))
+        let body_start: usize = head_end(&lines, index_header_end, s);
+        let remaining: &[u8] = &s.as_bytes()[body_start..];
+        // A response may announce a body it doesn't carry, e.g. one answering a `HEAD` request, so a missing body is fine.
+        // Otherwise it is framed the same way as by `parse_body`, but a chunked body keeps its framing
+        let framed: &[u8] = if remaining.is_empty() {
+            remaining
+        } else {
+            framed_body(&headers, remaining, false)?.unwrap_or_default()
+        };
+        let body: Option<Body> = match framed {
+            [] => None,
+            b => Some(Body::from_bytes(b.to_vec())),
         };
 
-        let collected_headers: Option<Vec<Header>> = if {headers.len()} == 0 {
+        let collected_headers: Option<Vec<Header>> = if headers.is_empty() {
             None
         } else {
             Some(headers)
         };
 
-        Ok(Self {
+        let res = Self {
             headers: collected_headers,
             version: Some(version),
             status: Some(code),
             body,
-        })
+            reason,
+            auto_content_length: false,
+        };
+        Ok((res, body_start + framed.len()))
+    }
+}
+
+/// Follows redirects for a client, counting them to stop redirect loops.
+///
+/// ```text
+/// let mut follower = RedirectFollower::new(5);
+/// while let Some(next) = follower.next_request(&response, &request)? {
+///     request = next;
+///     response = send(&request);
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RedirectFollower {
+    /// The maximum number of redirects to follow
+    pub max: usize,
+    /// The number of redirects followed so far
+    pub count: usize,
+}
+
+impl RedirectFollower {
+    pub fn new(max: usize) -> Self {
+        Self { max, count: 0 }
+    }
+
+    /// The request to send after receiving `resp` in answer to `prev`, built with [`RequestPacket::for_redirect`].
+    ///
+    /// `None` if `resp` is not a `301`, `302`, `303`, `307` or `308` response with a `Location` header.
+    /// Fails with [`PacketErr::TooManyRedirects`] if following it would exceed `max` redirects.
+    pub fn next_request(&mut self, resp: &ResponsePacket, prev: &RequestPacket) -> Result<Option<RequestPacket>, PacketErr> {
+        let Some(status) = &resp.status else {
+            return Ok(None);
+        };
+        if !matches!(status.as_int(), 301 | 302 | 303 | 307 | 308) {
+            return Ok(None);
+        }
+        let Some(location) = resp.get_header("Location") else {
+            return Ok(None);
+        };
+
+        if self.count >= self.max {
+            return Err(PacketErr::TooManyRedirects(self.max));
+        }
+        self.count += 1;
+        Ok(Some(prev.for_redirect(location, status)))
+    }
+}
+
+#[cfg(test)]
+mod redirect_follower_test {
+    use super::*;
+
+    fn redirect(location: &str) -> ResponsePacket {
+        ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Found)
+            .header(("Location", location))
+            .header(("Content-Length", "0"))
+            .try_build()
+            .unwrap()
+    }
+
+    #[test]
+    fn chain_exceeds_limit() {
+        let mut follower = RedirectFollower::new(2);
+        let first = RequestPacket::try_from_str("GET /a HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+
+        let second = follower.next_request(&redirect("/b"), &first).unwrap().unwrap();
+        assert_eq!(second.url, "/b");
+        let third = follower.next_request(&redirect("c"), &second).unwrap().unwrap();
+        assert_eq!(third.url, "/c");
+        assert_eq!(follower.count, 2);
+
+        assert_eq!(follower.next_request(&redirect("/d"), &third), Err(PacketErr::TooManyRedirects(2)));
+    }
+
+    #[test]
+    fn not_a_redirect() {
+        let mut follower = RedirectFollower::new(2);
+        let req = RequestPacket::try_from_str("GET / HTTP/1.1\r\n\r\n").unwrap();
+
+        let ok = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nLocation: /elsewhere\r\n\r\n").unwrap();
+        assert_eq!(follower.next_request(&ok, &req), Ok(None));
+        let not_modified = ResponsePacket::try_from_str("HTTP/1.1 304 Not Modified\r\n\r\n").unwrap();
+        assert_eq!(follower.next_request(&not_modified, &req), Ok(None));
+        let no_location = ResponsePacket::try_from_str("HTTP/1.1 302 Found\r\n\r\n").unwrap();
+        assert_eq!(follower.next_request(&no_location, &req), Ok(None));
+        assert_eq!(follower.count, 0);
+    }
+}
+
+#[cfg(test)]
+mod serialization_stability_test {
+    use super::*;
+
+    #[test]
+    fn stable_packets() {
+        let packets = [
+            "GET / HTTP/1.1\r\n\r\n",
+            "DELETE /items/1 HTTP/1.0\r\n\r\n",
+            "POST /submit HTTP/1.1\r\n\r\nname=value",
+            "GET /api\r\n\r\n",
+            "GET / HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\r\n",
+            "POST /form HTTP/1.0\r\nContent-Type:text/plain\r\nContent-Length:  5 \r\n\r\nhello",
+        ];
+        for p in packets {
+            assert!(is_serialization_stable(p), "{p:?} is not stable");
+        }
+    }
+
+    #[test]
+    fn unparsable_packets() {
+        assert!(!is_serialization_stable(""));
+        assert!(!is_serialization_stable("GARBAGE"));
+        assert!(!is_serialization_stable("GET / HTTP/1.1"));
+    }
+}
+
+#[cfg(test)]
+mod response_packet_test {
+    use super::*;
+
+    #[test]
+    fn try_from_str_not_found() {
+        let input = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+        let packet = ResponsePacket::try_from_str(input).unwrap();
+        assert_eq!(
+            packet,
+            ResponsePacket {
+                version: Version::V1_1,
+                status: Some(StatusCode::NotFound),
+                reason: None,
+                headers: Some(vec![Header { key: "Content-Length".into(), value: "0".into() }]),
+                body: None,
+            }
+        );
+        assert_eq!(packet.try_to_string(), Ok(input.to_string()));
+    }
+
+    #[test]
+    fn headers_eq_ignore_case() {
+        let res = ResponsePacket::try_from_str("HTTP/1.1 204 No Content\r\nServer: x\r\nVary: Origin\r\n\r\n").unwrap();
+        assert!(res.headers_eq_ignore_case(&[Header::new("vary", "Origin"), Header::new("SERVER", "x")]));
+        assert!(!res.headers_eq_ignore_case(&[Header::new("Server", "x")]));
+
+        let no_headers = ResponsePacket { version: Version::V0_9, status: None, reason: None, headers: None, body: Some("plain body".into()) };
+        assert!(no_headers.headers_eq_ignore_case(&[]));
+    }
+
+    #[test]
+    fn try_to_bytes_matches_try_into() {
+        let packet = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok").unwrap();
+        let into: Result<Vec<u8>, PacketErr> = packet.clone().try_into();
+        assert_eq!(packet.try_to_bytes(), into);
+        assert_eq!(packet.try_to_bytes(), Ok(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_vec()));
+    }
+
+    #[test]
+    fn get_header_set_cookie() {
+        let packet = ResponsePacket::try_from_str(concat!(
+            "HTTP/1.1 200 OK\r\n",
+            "Set-Cookie: a=1\r\n",
+            "content-type: text/plain\r\n",
+            "set-cookie: b=2\r\n",
+            "\r\n",
+        )).unwrap();
+        assert_eq!(packet.get_header("Content-Type"), Some("text/plain"));
+        assert_eq!(packet.get_header("Set-Cookie"), Some("a=1"));
+        assert_eq!(packet.get_header_all("SET-COOKIE"), vec!["a=1", "b=2"]);
+
+        let no_headers = ResponsePacket::try_from_str("HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+        assert_eq!(no_headers.get_header("Set-Cookie"), None);
+    }
+
+    #[test]
+    fn wire_size_matches_input() {
+        let input = "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: 9\r\n\r\nnot found";
+        assert_eq!(ResponsePacket::try_from_str_with_wire_size(input).unwrap().1, input.len());
+
+        let no_headers = "HTTP/1.0 204 No Content\r\n\r\n";
+        assert_eq!(ResponsePacket::try_from_str_with_wire_size(no_headers).unwrap().1, no_headers.len());
+
+        let compact = "HTTP/1.1 200 OK\r\nServer:x\r\n\r\n";
+        assert_eq!(ResponsePacket::try_from_str_with_wire_size(compact).unwrap().1, compact.len());
+
+        let unterminated = "HTTP/1.1 200 OK\r\nA: b\r\n";
+        assert_eq!(ResponsePacket::try_from_str_with_wire_size(unterminated).unwrap().1, unterminated.len());
+
+        let chunked = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nok\r\n0\r\n\r\nHTTP/1.1 204 No Content\r\n\r\n";
+        let (_, wire_size) = ResponsePacket::try_from_str_with_wire_size(chunked).unwrap();
+        assert_eq!(&chunked[wire_size..], "HTTP/1.1 204 No Content\r\n\r\n");
+    }
+
+    #[test]
+    fn binary_body_round_trip() {
+        let payload: Vec<u8> = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0xff];
+        let head: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 9\r\n\r\n";
+        let mut input: Vec<u8> = head.to_vec();
+        input.extend_from_slice(&payload);
+
+        let headers: Vec<Header> = vec![
+            Header::try_from("Content-Type: image/png").unwrap(),
+            Header::try_from("Content-Length: 9").unwrap(),
+        ];
+        let body: Option<Body> = parse_body(&headers, &input[head.len()..], None).unwrap();
+        assert_eq!(body, Some(Body::Bytes(payload.clone())));
+
+        let packet = ResponsePacket {
+            version: Version::V1_1,
+            status: Some(StatusCode::Ok),
+            reason: None,
+            headers: Some(headers),
+            body,
+        };
+        let bytes: Vec<u8> = packet.clone().try_into().unwrap();
+        assert_eq!(bytes, input);
+        assert_eq!(packet.try_to_string(), Err(PacketErr::NonUtf8Body));
+    }
+
+    #[test]
+    fn links() {
+        let packet = ResponsePacket::try_from_str(
+            "HTTP/1.1 200 OK\r\nLink: <https://x>; rel=\"next\", <https://y>; rel=\"prev\"\r\n\r\n"
+        ).unwrap();
+        let links = packet.links();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].uri, "https://x");
+        assert_eq!(links[0].param("rel"), Some("next"));
+        assert_eq!(links[1].uri, "https://y");
+        assert_eq!(links[1].param("rel"), Some("prev"));
+    }
+
+    #[test]
+    fn accept_ranges() {
+        let bytes = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nAccept-Ranges: bytes\r\n\r\n").unwrap();
+        assert!(bytes.accepts_ranges());
+        assert_eq!(bytes.accept_ranges_unit(), Some("bytes".to_string()));
+
+        let none = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nAccept-Ranges: none\r\n\r\n").unwrap();
+        assert!(!none.accepts_ranges());
+        assert_eq!(none.accept_ranges_unit(), Some("none".to_string()));
+
+        let absent = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\n\r\n").unwrap();
+        assert!(!absent.accepts_ranges());
+        assert_eq!(absent.accept_ranges_unit(), None);
+    }
+
+    #[test]
+    fn expires() {
+        let packet = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nExpires: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\n").unwrap();
+        assert_eq!(packet.expires(), Some(784111777));
+
+        let zero = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nExpires: 0\r\n\r\n").unwrap();
+        assert_eq!(zero.expires(), Some(0));
+
+        let invalid = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nExpires: tomorrow\r\n\r\n").unwrap();
+        assert_eq!(invalid.expires(), None);
+        assert_eq!(ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\n\r\n").unwrap().expires(), None);
+    }
+
+    #[test]
+    fn fresh_by_max_age() {
+        // Sun, 06 Nov 1994 08:49:37 GMT
+        let received: u64 = 784111777;
+        let packet = ResponsePacket::try_from_str(concat!(
+            "HTTP/1.1 200 OK\r\n",
+            "Date: Sun, 06 Nov 1994 08:49:37 GMT\r\n",
+            "Cache-Control: public, max-age=60\r\n",
+            "Expires: Sun, 06 Nov 1994 08:49:38 GMT\r\n",
+            "\r\n",
+        )).unwrap();
+        // max-age takes precedence over Expires
+        assert!(packet.is_fresh(received, received + 59));
+        assert!(!packet.is_fresh(received, received + 60));
+    }
+
+    #[test]
+    fn expired() {
+        let received: u64 = 784111777;
+        let packet = ResponsePacket::try_from_str(concat!(
+            "HTTP/1.1 200 OK\r\n",
+            "Date: Sun, 06 Nov 1994 08:49:37 GMT\r\n",
+            "Expires: Sun, 06 Nov 1994 08:50:37 GMT\r\n",
+            "Age: 30\r\n",
+            "\r\n",
+        )).unwrap();
+        assert!(packet.is_fresh(received, received + 29));
+        assert!(!packet.is_fresh(received, received + 30));
+
+        let invalid_expires = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nExpires: 0\r\n\r\n").unwrap();
+        assert!(!invalid_expires.is_fresh(received, received));
+
+        let no_cache = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nCache-Control: no-cache, max-age=60\r\n\r\n").unwrap();
+        assert!(!no_cache.is_fresh(received, received));
+    }
+
+    #[test]
+    fn set_cookies() {
+        let res = ResponsePacket::try_from_str(concat!(
+            "HTTP/1.1 200 OK\r\n",
+            "Set-Cookie: session=abc123; Path=/; Max-Age=3600; Secure; HttpOnly\r\n",
+            "Set-Cookie: theme=dark; Domain=example.com\r\n",
+            "Content-Length: 0\r\n",
+            "\r\n",
+        )).unwrap();
+        let cookies: Vec<Cookie> = res.set_cookies();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].name, "session");
+        assert_eq!(cookies[0].path.as_deref(), Some("/"));
+        assert_eq!(cookies[0].max_age, Some(3600));
+        assert!(cookies[0].secure && cookies[0].http_only);
+        assert_eq!(cookies[1], Cookie {
+            name: "theme".to_string(),
+            value: "dark".to_string(),
+            domain: Some("example.com".to_string()),
+            ..Cookie::default()
+        });
+    }
+
+    #[test]
+    fn must_close() {
+        let parse = |s: &str| ResponsePacket::try_from_str(s).unwrap();
+        assert!(!parse("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok").must_close());
+        assert!(!parse("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nok\r\n0\r\n\r\n").must_close());
+        assert!(!parse("HTTP/1.1 204 No Content\r\n\r\n").must_close());
+
+        // Connection: close
+        assert!(parse("HTTP/1.1 200 OK\r\nConnection: Close\r\nContent-Length: 2\r\n\r\nok").must_close());
+        // a body framed by the connection closing
+        assert!(parse("HTTP/1.1 200 OK\r\n\r\nok").must_close());
+        assert!(parse("HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip\r\n\r\nok").must_close());
+    }
+
+    #[test]
+    fn respond_to() {
+        let req = RequestPacket::try_from_str("GET /hello HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+        let res = ResponsePacket::respond_to(&req, StatusCode::Ok, "Hello!");
+        assert_eq!(
+            res.try_to_string().unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 6\r\nConnection: keep-alive\r\n\r\nHello!"
+        );
+
+        let closing = RequestPacket::try_from_str("GET / HTTP/1.1\r\nConnection: Close\r\n\r\n").unwrap();
+        assert_eq!(ResponsePacket::respond_to(&closing, StatusCode::NotFound, "").get_header("Connection"), Some("close"));
+
+        let http_1_0 = RequestPacket::try_from_str("GET / HTTP/1.0\r\n\r\n").unwrap();
+        let res = ResponsePacket::respond_to(&http_1_0, StatusCode::Ok, "");
+        assert_eq!(res.version, Version::V1_0);
+        assert_eq!(res.get_header("Connection"), Some("close"));
+        assert_eq!(res.get_header("Content-Length"), Some("0"));
+        assert_eq!(res.body, None);
+    }
+
+    #[test]
+    fn respond_to_without_body() {
+        let head = RequestPacket::try_from_str("HEAD / HTTP/1.1\r\n\r\n").unwrap();
+        let res = ResponsePacket::respond_to(&head, StatusCode::Ok, "Hello!");
+        assert_eq!(res.get_header("Content-Length"), Some("6"));
+        assert_eq!(res.body, None);
+
+        let get = RequestPacket::try_from_str("GET / HTTP/1.1\r\n\r\n").unwrap();
+        let res = ResponsePacket::respond_to(&get, StatusCode::NoContent, "ignored");
+        assert_eq!(res.try_to_string().unwrap(), "HTTP/1.1 204 No Content\r\nConnection: keep-alive\r\n\r\n");
+    }
+
+    #[test]
+    fn try_from_str_0_9() {
+        assert!(ResponsePacket::try_from_str("<p>That's it</p>").is_err());
+    }
+
+    #[test]
+    fn options_response_headers() {
+        let packet = ResponsePacket::options_response(Version::V1_1, &[Method::Get, Method::Head, Method::Options]);
+        assert_eq!(packet.status, Some(StatusCode::Ok));
+        assert_eq!(
+            packet.headers,
+            Some(vec![
+                Header { key: "Allow".into(), value: "GET, HEAD, OPTIONS".into() },
+                Header { key: "Content-Length".into(), value: "0".into() },
+            ])
+        );
+        assert_eq!(
+            packet.try_to_string(),
+            Ok("HTTP/1.1 200 OK\r\nAllow: GET, HEAD, OPTIONS\r\nContent-Length: 0\r\n\r\n".to_string())
+        );
+    }
+
+    #[test]
+    fn transfer_encodings() {
+        let valid = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .header(("Transfer-Encoding", "gzip, chunked"))
+            .try_build()
+            .unwrap();
+        assert_eq!(valid.transfer_encodings(), vec!["gzip".to_string(), "chunked".to_string()]);
+        assert_eq!(valid.validate(), Ok(()));
+
+        let invalid = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .header(("Transfer-Encoding", "chunked, chunked"))
+            .try_build()
+            .unwrap();
+        assert_eq!(invalid.validate(), Err(PacketErr::InvalidTransferEncoding));
+
+        let none = ResponsePacket::options_response(Version::V1_1, &[Method::Get]);
+        assert!(none.transfer_encodings().is_empty());
+        assert_eq!(none.validate(), Ok(()));
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn text_decoded_latin1() {
+        let mut packet = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .header(("Content-Type", "text/plain; charset=ISO-8859-1"))
+            .try_build()
+            .unwrap();
+        // "café à Zürich" in Latin-1
+        packet.body = Some(Body::Bytes(b"caf\xe9 \xe0 Z\xfcrich".to_vec()));
+        assert_eq!(packet.text_decoded(), Some("café à Zürich".to_string()));
+
+        // without a charset, the body is UTF-8
+        packet.headers = Some(vec![Header::new("Content-Type", "text/plain")]);
+        assert_eq!(packet.text_decoded(), None);
+        packet.body = Some(Body::from("café"));
+        assert_eq!(packet.text_decoded(), Some("café".to_string()));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn decompressed_body_identity() {
+        let packet = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .header(("Content-Encoding", "identity"))
+            .body("plain")
+            .try_build()
+            .unwrap();
+        assert_eq!(packet.decompressed_body(), Ok(Some(b"plain".to_vec())));
+
+        let unsupported = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .header(("Content-Encoding", "compress"))
+            .body("plain")
+            .try_build()
+            .unwrap();
+        assert_eq!(
+            unsupported.decompressed_body(),
+            Err(PacketErr::UnsupportedContentEncoding("compress".to_string()))
+        );
+    }
+}
+
+#[cfg(test)]
+mod response_packet_builder_test {
+    use super::*;
+
+    #[test]
+    fn no_headers() {
+        let input = "HTTP/1.1 204 No Content\r\n\r\nbody";
+        let builder = ResponsePacketBuilder::try_from_str(input).unwrap();
+        assert_eq!(builder.version, Some(Version::V1_1));
+        assert_eq!(builder.status, Some(StatusCode::NoContent));
+        assert_eq!(builder.headers, None);
+        assert_eq!(builder.body, Some(Body::Text("body".into())));
+
+        let packet = builder.try_build().unwrap();
+        assert_eq!(packet.try_to_string(), Ok(input.to_string()));
+    }
+
+    #[test]
+    fn no_headers_no_body() {
+        let input = "HTTP/1.0 200 OK\r\n\r\n";
+        let packet = ResponsePacket::try_from_str(input).unwrap();
+        assert_eq!(packet.headers, None);
+        assert_eq!(packet.body, None);
+        assert_eq!(packet.try_to_string(), Ok(input.to_string()));
+    }
+
+    #[test]
+    fn auto_content_length() {
+        let packet = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .auto_content_length()
+            .body("h\u{e9}llo")
+            .try_build()
+            .unwrap();
+        assert_eq!(
+            packet.try_to_string(),
+            Ok("HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\nh\u{e9}llo".to_string())
+        );
+
+        // a chunked body is framed by its chunks
+        let chunked = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .chunked_body(&["hello"])
+            .auto_content_length()
+            .try_build()
+            .unwrap();
+        assert_eq!(chunked.get_header("Content-Length"), None);
+        assert_eq!(chunked.validate(), Ok(()));
+    }
+
+    #[test]
+    fn chunked_body() {
+        let chunks: [&str; 4] = ["Hello, ", "", "w\u{f6}rld", "!"];
+        let packet = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .chunked_body(&chunks)
+            .try_build()
+            .unwrap();
+        assert_eq!(packet.get_header("Transfer-Encoding"), Some("chunked"));
+
+        let framed: &str = packet.body.as_ref().unwrap().as_str().unwrap();
+        assert_eq!(framed, "7\r\nHello, \r\n6\r\nw\u{f6}rld\r\n1\r\n!\r\n0\r\n\r\n");
+        assert_eq!(
+            decode_chunked(framed.as_bytes()),
+            Ok((chunks.concat().into_bytes(), framed.len()))
+        );
+    }
+
+    #[test]
+    fn strict_body_for_status() {
+        let builder = |status: StatusCode| ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(status)
+            .body("content");
+
+        assert_eq!(builder(StatusCode::NoContent).try_build_strict(), Err(PacketErr::BodyNotAllowedForStatus(204)));
+        assert_eq!(builder(StatusCode::NotModified).try_build_strict(), Err(PacketErr::BodyNotAllowedForStatus(304)));
+        assert_eq!(builder(StatusCode::Continue).try_build_strict(), Err(PacketErr::BodyNotAllowedForStatus(100)));
+        // the lenient build lets it through
+        assert!(builder(StatusCode::NoContent).try_build().is_ok());
+
+        let ok = builder(StatusCode::Ok).try_build_strict().unwrap();
+        assert_eq!(ok.body, Some(Body::from("content")));
+        assert!(ResponsePacketBuilder::new().version(Version::V1_1).status(StatusCode::NoContent).try_build_strict().is_ok());
+    }
+
+    #[test]
+    fn status_with_reason() {
+        let packet = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status_with_reason(200, "Okay Dokay")
+            .unwrap()
+            .header(("Content-Length", "0"))
+            .try_build()
+            .unwrap();
+        assert_eq!(packet.try_to_string().unwrap(), "HTTP/1.1 200 Okay Dokay\r\nContent-Length: 0\r\n\r\n");
+        assert_eq!(packet.status, Some(StatusCode::Ok));
+        assert_eq!(packet.reason.as_deref(), Some("Okay Dokay"));
+        // parsing the status line gives the same packet back
+        assert_eq!(ResponsePacket::try_from_str(&packet.try_to_string().unwrap()).unwrap(), packet);
+
+        // a redirect status still switches the method
+        let see_other = ResponsePacketBuilder::new().version(Version::V1_1).status_with_reason(303, "Look There").unwrap();
+        assert_eq!(see_other.status, Some(StatusCode::SeeOther));
+
+        // codes without a variant keep the phrase in the status
+        let custom = ResponsePacketBuilder::new().version(Version::V1_1).status_with_reason(599, "Network Timeout").unwrap();
+        assert_eq!(custom.clone().try_build().unwrap().status, Some(StatusCode::Custom(599, "Network Timeout".to_string())));
+        // setting the status again drops the phrase
+        assert_eq!(custom.status(StatusCode::Ok).try_build().unwrap().reason, None);
+
+        assert_eq!(ResponsePacketBuilder::new().status_with_reason(99, "Too Low").err(), Some(PacketErr::InvalidStatusCode(99)));
+        assert_eq!(ResponsePacketBuilder::new().status_with_reason(600, "Too High").err(), Some(PacketErr::InvalidStatusCode(600)));
+        assert!(ResponsePacketBuilder::new().status_with_reason(599, "Last").is_ok());
+    }
+
+    #[test]
+    fn request_id() {
+        let req = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url("/")
+            .version(Version::V1_1)
+            .request_id("f058ebd6-02f7-4d3f-942e-904344e8cde5")
+            .try_build()
+            .unwrap();
+        assert_eq!(req.request_id(), Some("f058ebd6-02f7-4d3f-942e-904344e8cde5"));
+
+        let res = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .request_id(req.request_id().unwrap())
+            .try_build()
+            .unwrap();
+        assert_eq!(res.request_id(), req.request_id());
+        assert_eq!(ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\n\r\n").unwrap().request_id(), None);
+    }
+
+    #[test]
+    fn etags() {
+        let strong = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .strong_etag("33a64df5")
+            .try_build()
+            .unwrap();
+        assert_eq!(
+            strong.try_to_string(),
+            Ok("HTTP/1.1 200 OK\r\nETag: \"33a64df5\"\r\n\r\n".to_string())
+        );
+
+        let weak = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .weak_etag("\"0815\"")
+            .try_build()
+            .unwrap();
+        assert_eq!(
+            weak.try_to_string(),
+            Ok("HTTP/1.1 200 OK\r\nETag: W/\"0815\"\r\n\r\n".to_string())
+        );
+    }
+
+    #[test]
+    fn cors_headers() {
+        let res = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::NoContent)
+            .cors("https://example.com", &[Method::Get, Method::Post], &["Content-Type", "X-Token"])
+            .try_build()
+            .unwrap();
+        assert_eq!(
+            res.try_to_string(),
+            Ok(concat!(
+                "HTTP/1.1 204 No Content\r\n",
+                "Access-Control-Allow-Origin: https://example.com\r\n",
+                "Access-Control-Allow-Methods: GET, POST\r\n",
+                "Access-Control-Allow-Headers: Content-Type, X-Token\r\n",
+                "\r\n",
+            ).to_string())
+        );
+    }
+
+    #[test]
+    fn matching_request_version() {
+        let req = RequestPacket::try_from_str("GET / HTTP/1.0\r\n\r\n").unwrap();
+        let res = ResponsePacketBuilder::new()
+            .matching_request(&req)
+            .status(StatusCode::Ok)
+            .try_build()
+            .unwrap();
+        assert_eq!(res.version, Version::V1_0);
+        assert_eq!(res.try_to_string(), Ok("HTTP/1.0 200 OK\r\n\r\n".to_string()));
+    }
+
+    #[test]
+    fn matching_request_0_9() {
+        let req = RequestPacket::try_from_str("GET /\r\n\r\n").unwrap();
+        let res = ResponsePacketBuilder::new()
+            .matching_request(&req)
+            .status(StatusCode::Ok)
+            .body("<p>hi</p>")
+            .try_build()
+            .unwrap();
+        assert_eq!(res.version, Version::V0_9);
+        assert_eq!(res.try_to_string(), Ok("<p>hi</p>".to_string()));
+    }
+
+    #[test]
+    fn malformed_header_line_number() {
+        let input = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nNoColonHere\r\n\r\n";
+        assert_eq!(
+            ResponsePacketBuilder::try_from_str(input),
+            Err(PacketErr::MalformedHeaderAtLine(3, "NoColonHere".to_string()))
+        );
+    }
+
+    #[test]
+    fn http_2_0() {
+        let input = "HTTP/2.0 200 OK\r\nContent-Length: 2\r\n\r\nhi";
+        let packet = ResponsePacket::try_from_str(input).unwrap();
+        assert_eq!(packet.version, Version::V2_0);
+        assert_eq!(packet.try_to_string(), Ok(input.to_string()));
+
+        // same required fields as HTTP/1.1
+        assert_eq!(
+            ResponsePacketBuilder::new().version(Version::V2_0).try_build(),
+            Err(PacketErr::NoStatusCode)
+        );
+    }
+
+    #[test]
+    fn no_header_end() {
+        assert_eq!(
+            ResponsePacketBuilder::try_from_str("HTTP/1.0 200 OK\r\n"),
+            Err(PacketErr::InvalidLines)
+        );
+    }
+}
+
+#[cfg(test)]
+mod parse_body_test {
+    use super::*;
+
+    fn hdrs(pairs: &[(&str, &str)]) -> Vec<Header> {
+        pairs.iter().map(|(k, v)| Header { key: k.to_string(), value: v.to_string() }).collect()
+    }
+
+    #[test]
+    fn content_length() {
+        let headers = hdrs(&[("Content-Length", "5")]);
+        assert_eq!(
+            parse_body(&headers, b"helloGET / HTTP/1.1", Some(Method::Post)),
+            Ok(Some(Body::Text("hello".into())))
+        );
+        assert_eq!(
+            parse_body(&headers, b"hel", Some(Method::Post)),
+            Err(PacketErr::InvalidLines)
+        );
+    }
+
+    #[test]
+    fn invalid_content_length() {
+        assert_eq!(
+            parse_body(&hdrs(&[("Content-Length", "five")]), b"hello", None),
+            Err(PacketErr::InvalidContentLength)
+        );
+        assert_eq!(
+            parse_body(&hdrs(&[("Content-Length", "5"), ("Content-Length", "4")]), b"hello", None),
+            Err(PacketErr::InvalidContentLength)
+        );
+    }
+
+    #[test]
+    fn chunked() {
+        let headers = hdrs(&[("Transfer-Encoding", "chunked")]);
+        assert_eq!(
+            parse_body(&headers, b"4\r\nWiki\r\n7;ext=1\r\npedia i\r\nB\r\nn \r\nchunks.\r\n0\r\nExpires: 0\r\n\r\n", None),
+            Ok(Some(Body::Text("Wikipedia in \r\nchunks.".into())))
+        );
+    }
+
+    #[test]
+    fn malformed_chunked() {
+        let headers = hdrs(&[("Transfer-Encoding", "chunked")]);
+        // bad size
+        assert_eq!(parse_body(&headers, b"zz\r\nWiki\r\n0\r\n\r\n", None), Err(PacketErr::MalformedChunkedBody));
+        // chunk shorter than announced
+        assert_eq!(parse_body(&headers, b"9\r\nWiki\r\n0\r\n\r\n", None), Err(PacketErr::MalformedChunkedBody));
+        // no terminating chunk
+        assert_eq!(parse_body(&headers, b"4\r\nWiki\r\n", None), Err(PacketErr::MalformedChunkedBody));
+        // chunk sizes near usize::MAX
+        assert_eq!(parse_body(&headers, b"FFFFFFFFFFFFFFED\r\nab", None), Err(PacketErr::MalformedChunkedBody));
+        assert_eq!(parse_body(&headers, b"FFFFFFFFFFFFFFFF\r\nab", None), Err(PacketErr::MalformedChunkedBody));
+    }
+
+    #[test]
+    fn no_framing() {
+        // requests without framing have no body
+        assert_eq!(parse_body(&[], b"leftover", Some(Method::Get)), Ok(None));
+        // responses read until the end
+        assert_eq!(parse_body(&[], b"until close", None), Ok(Some(Body::Text("until close".into()))));
+        assert_eq!(parse_body(&[], b"", None), Ok(None));
+    }
+
+    #[test]
+    fn non_utf8() {
+        assert_eq!(
+            parse_body(&hdrs(&[("Content-Length", "2")]), &[0xff, 0xfe], None),
+            Ok(Some(Body::Bytes(vec![0xff, 0xfe])))
+        );
+    }
+
+    #[test]
+    fn same_framing_as_try_from_str() {
+        // the packet keeps the chunk framing, and the body ends with the last chunk
+        let rp = RequestPacket::try_from_str(
+            "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\nGET /next HTTP/1.1\r\n\r\n"
+        ).unwrap();
+        let framed: &[u8] = rp.body.as_ref().unwrap().as_bytes();
+        assert_eq!(framed, b"5\r\nhello\r\n0\r\n\r\n");
+        assert_eq!(parse_body(&rp.headers, framed, Some(Method::Post)), Ok(Some(Body::Text("hello".into()))));
+
+        let res = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nok\r\n0\r\n\r\nextra").unwrap();
+        assert_eq!(res.body, Some(Body::Text("2\r\nok\r\n0\r\n\r\n".into())));
+
+        // requests without framing have no body
+        assert_eq!(RequestPacket::try_from_str("GET / HTTP/1.1\r\n\r\nleftover").unwrap().body, None);
+        assert_eq!(
+            RequestPacket::try_from_str("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n"),
+            Err(PacketErr::MalformedChunkedBody)
+        );
     }
 }
 
 #[cfg(test)]
 mod request_packet_builder_test {
     use super::*;
+    #[test]
+    fn http_2_0_request() {
+        let input = "GET / HTTP/2.0\r\nHost: example.com\r\n\r\n";
+        let packet = RequestPacket::try_from_str(input).unwrap();
+        assert_eq!(packet.version, Version::V2_0);
+        assert_eq!(packet.to_string(), input);
+    }
+
+    #[test]
+    fn tab_separated_request_line() {
+        let packet = RequestPacket::try_from_str("GET\t/\tHTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(packet.method, Method::Get);
+        assert_eq!(packet.url, "/");
+        assert_eq!(packet.version, Version::V1_1);
+        // re-serialization normalizes the separators to spaces
+        assert_eq!(packet.to_string(), "GET / HTTP/1.1\r\n\r\n");
+    }
+
+    #[test]
+    fn lenient_line_endings() {
+        let input = "POST /submit HTTP/1.1\nHost: example.com\nContent-Length: 6\n\nab\ncd\n";
+        assert!(RequestPacketBuilder::try_from_str(input).is_err());
+
+        let packet = RequestPacketBuilder::try_from_str_lenient(input).unwrap().try_build().unwrap();
+        assert_eq!(packet.url, "/submit");
+        assert_eq!(packet.get_header("Host"), Some("example.com"));
+        // the body keeps its bare line endings
+        assert_eq!(packet.body, Some(Body::from("ab\ncd\n")));
+
+        // \r\n line endings are kept as they are
+        let strict = "GET / HTTP/1.1\r\nHost: a\nAccept: */*\r\n\r\n";
+        let packet = RequestPacketBuilder::try_from_str_lenient(strict).unwrap().try_build().unwrap();
+        assert_eq!(packet.to_string(), "GET / HTTP/1.1\r\nHost: a\r\nAccept: */*\r\n\r\n");
+    }
+
+    #[test]
+    fn lenient_line_endings_response() {
+        let packet = ResponsePacketBuilder::try_from_str_lenient("HTTP/1.1 404 Not Found\nContent-Length: 0\n\n")
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(packet.status, Some(StatusCode::NotFound));
+        assert_eq!(packet.get_header("Content-Length"), Some("0"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_str() {
+        let packet = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/api")
+            .version(Version::V1_1)
+            .json_str(r#"{"name": "httpsplitter"}"#)
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(packet.get_header("Content-Type"), Some("application/json"));
+        assert_eq!(packet.get_header("Content-Length"), Some("24"));
+        assert_eq!(packet.body, Some(Body::from(r#"{"name": "httpsplitter"}"#)));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_str_invalid() {
+        let res = RequestPacketBuilder::new().json_str(r#"{"name": }"#);
+        assert!(matches!(res, Err(PacketErr::InvalidJsonBody)));
+    }
+
+    #[test]
+    fn shortcuts() {
+        let short = RequestPacket::builder()
+            .get()
+            .path("/x")
+            .http_1_1()
+            .header(("Host", "example.com"))
+            .try_build()
+            .unwrap();
+        let long = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url("/x")
+            .version(Version::V1_1)
+            .header(("Host", "example.com"))
+            .try_build()
+            .unwrap();
+        assert_eq!(short, long);
+
+        let post = RequestPacket::builder().post().path("/submit").http_1_0().body("a=1").try_build().unwrap();
+        assert_eq!(post.method, Method::Post);
+        assert_eq!(post.version, Version::V1_0);
+
+        let pairs: [(RequestPacketBuilder, Method); 9] = [
+            (RequestPacketBuilder::new().get(), Method::Get),
+            (RequestPacketBuilder::new().head(), Method::Head),
+            (RequestPacketBuilder::new().options(), Method::Options),
+            (RequestPacketBuilder::new().trace(), Method::Trace),
+            (RequestPacketBuilder::new().put(), Method::Put),
+            (RequestPacketBuilder::new().delete(), Method::Delete),
+            (RequestPacketBuilder::new().post(), Method::Post),
+            (RequestPacketBuilder::new().patch(), Method::Patch),
+            (RequestPacketBuilder::new().connect(), Method::Connect),
+        ];
+        for (builder, method) in pairs {
+            assert_eq!(builder.method, Some(method));
+        }
+        assert_eq!(RequestPacketBuilder::new().http_0_9().version, Some(Version::V0_9));
+        assert_eq!(RequestPacketBuilder::new().http_2_0().version, Some(Version::V2_0));
+    }
+
+    #[test]
+    fn http_0_9_only_get() {
+        assert_eq!(RequestPacket::try_from_str("POST /\r\n\r\n"), Err(PacketErr::InvalidMethodForVersion));
+        let get = RequestPacket::try_from_str("GET /\r\n\r\n").unwrap();
+        assert_eq!(get.version, Version::V0_9);
+        assert_eq!(get.method, Method::Get);
+        assert_eq!(
+            RequestPacket::builder().head().path("/").http_0_9().try_build(),
+            Err(PacketErr::InvalidMethodForVersion)
+        );
+    }
+
+    #[test]
+    fn set_header() {
+        let packet = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url("/")
+            .version(Version::V1_1)
+            .header(("Accept", "*/*"))
+            .set_header(("Host", "a"))
+            .header(("host", "duplicate"))
+            .header(("User-Agent", "httpsplitter"))
+            .set_header(("Host", "b"))
+            .try_build()
+            .unwrap();
+        assert_eq!(packet.get_header_all("Host"), vec!["b"]);
+        // the replaced header keeps its position
+        assert_eq!(packet.header_order(), vec!["accept", "host", "user-agent"]);
+    }
+
+    #[test]
+    fn remove_header() {
+        let builder = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url("/")
+            .version(Version::V1_1)
+            .header(("Authorization", "Bearer secret"))
+            .header(("Accept", "*/*"))
+            .header(("authorization", "Basic c2VjcmV0"));
+        assert!(builder.has_header("AUTHORIZATION"));
+
+        let builder = builder.remove_header("Authorization").remove_header("X-Missing");
+        assert!(!builder.has_header("Authorization"));
+        assert!(builder.has_header("accept"));
+        assert_eq!(builder.try_build().unwrap().header_order(), vec!["accept"]);
+
+        assert!(!RequestPacketBuilder::new().remove_header("Accept").has_header("Accept"));
+    }
+
+    #[test]
+    fn remove_header_response() {
+        let builder = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .header(("Set-Cookie", "a=1"))
+            .header(("Cache-Control", "no-store"))
+            .remove_header("set-cookie");
+        assert!(!builder.has_header("Set-Cookie"));
+        assert!(builder.has_header("Cache-Control"));
+    }
+
+    #[test]
+    fn set_header_response() {
+        let packet = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .set_header(("Content-Length", "3"))
+            .body("abcde")
+            .set_header(("content-length", "5"))
+            .try_build()
+            .unwrap();
+        assert_eq!(packet.get_header_all("Content-Length"), vec!["5"]);
+    }
+
+    #[test]
+    fn invalid_url() {
+        let builder = || RequestPacketBuilder::new().method(Method::Get).version(Version::V1_1);
+        assert_eq!(builder().url("/a\tb").try_build(), Err(PacketErr::InvalidUrl));
+        assert_eq!(builder().url("/a b").try_build(), Err(PacketErr::InvalidUrl));
+        assert_eq!(
+            RequestPacket::try_from_str("GET /a\u{1}b HTTP/1.1\r\n\r\n"),
+            Err(PacketErr::InvalidUrl)
+        );
+        assert_eq!(
+            RequestPacket::try_from_str("GET /a\u{7f} HTTP/1.1\r\n\r\n"),
+            Err(PacketErr::InvalidUrl)
+        );
+    }
+
+    #[test]
+    fn valid_url() {
+        let packet = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url("/search?q=a%20b&lang=\u{fc}")
+            .version(Version::V1_1)
+            .try_build()
+            .unwrap();
+        assert_eq!(packet.url, "/search?q=a%20b&lang=\u{fc}");
+        assert!(RequestPacket::try_from_str("GET /a/b?c=d HTTP/1.1\r\n\r\n").is_ok());
+    }
+
+    #[test]
+    fn leading_whitespace_request_line() {
+        let input = "   GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let builder = RequestPacketBuilder::try_from_str(input).unwrap();
+        assert_eq!(builder.method, Some(Method::Get));
+        assert_eq!(builder.url, Some("/index.html".to_string()));
+        assert_eq!(builder.version, Some(Version::V1_1));
+
+        assert_eq!(
+            parse_request_line("   GET /index.html HTTP/1.1"),
+            Ok((Method::Get, "/index.html".to_string(), Version::V1_1))
+        );
+        assert_eq!(
+            Version::try_from_first_req_line("   GET /index.html HTTP/1.1"),
+            Ok(Version::V1_1)
+        );
+
+        // re-serialization drops the whitespace
+        let packet = builder.try_build().unwrap();
+        assert!(packet.to_string().starts_with("GET /index.html HTTP/1.1\r\n"));
+    }
+
+    #[test]
+    fn auto_content_length_multi_byte() {
+        let rp = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/")
+            .version(Version::V1_1)
+            .auto_content_length()
+            .body("h\u{e9}llo")
+            .try_build()
+            .unwrap();
+        // 'é' takes up 2 bytes
+        assert_eq!(rp.get_header("Content-Length"), Some("6"));
+        assert_eq!(
+            RequestPacketBuilder::new()
+                .method(Method::Post)
+                .url("/")
+                .version(Version::V1_1)
+                .body("h\u{e9}llo")
+                .content_length()
+                .try_build()
+                .unwrap()
+                .get_header("Content-Length"),
+            Some("6")
+        );
+    }
+
+    #[test]
+    fn auto_content_length_keeps_explicit_header() {
+        let rp = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/")
+            .version(Version::V1_1)
+            .auto_content_length()
+            .header(("content-length", "3"))
+            .body("abc")
+            .try_build()
+            .unwrap();
+        assert_eq!(rp.get_header_all("Content-Length"), vec!["3"]);
+
+        // no body, no header
+        let no_body = RequestPacketBuilder::new()
+            .method(Method::Get)
+            .url("/")
+            .version(Version::V1_1)
+            .auto_content_length()
+            .try_build()
+            .unwrap();
+        assert_eq!(no_body.get_header("Content-Length"), None);
+
+        let chunked = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/")
+            .version(Version::V1_1)
+            .auto_content_length()
+            .header(("Transfer-Encoding", "gzip, chunked"))
+            .body("0\r\n\r\n")
+            .try_build()
+            .unwrap();
+        assert_eq!(chunked.get_header("Content-Length"), None);
+    }
+
+    #[test]
+    fn content_length_exact_body() {
+        let builder = RequestPacketBuilder::try_from_str("POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\nab\r\n\r\ncde").unwrap();
+        assert_eq!(builder.body, Some(Body::Text("ab\r\n\r\ncde".to_string())));
+    }
+
+    #[test]
+    fn content_length_over_length_input() {
+        // the bytes after the announced length are ignored
+        let builder = RequestPacketBuilder::try_from_str("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello\r\n\r\ngarbage").unwrap();
+        assert_eq!(builder.body, Some(Body::Text("hello".to_string())));
+
+        // not enough bytes
+        assert_eq!(
+            RequestPacketBuilder::try_from_str("POST / HTTP/1.1\r\nContent-Length: 50\r\n\r\nhello").err(),
+            Some(PacketErr::InvalidLines)
+        );
+    }
+
+    #[test]
+    fn malformed_header_line_number() {
+        let input = "GET / HTTP/1.1\r\nHost: example.com\r\nNoColonHere\r\n\r\n";
+        assert_eq!(
+            RequestPacketBuilder::try_from_str(input),
+            Err(PacketErr::MalformedHeaderAtLine(3, "NoColonHere".to_string()))
+        );
+    }
+
     #[test]
     fn too_many_words() {
         let input = "GET /api HTTP/1.0 a";
@@ -668,12 +3881,107 @@ mod request_packet_builder_test {
 
 #[cfg(test)]
 mod random_body_test {
-    use super::*;
-    
     #[test]
     fn joined() {
-        let v = vec!["a"];
+        let v = ["a"];
         let joined = v.join("\r\n");
         assert_eq!(joined, "a");
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_test {
+    use super::*;
+
+    #[cfg(feature = "json")]
+    #[derive(serde::Serialize)]
+    struct Item {
+        id: u32,
+        name: &'static str,
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_body() {
+        let req = RequestPacketBuilder::new()
+            .method(Method::Post)
+            .url("/items")
+            .version(Version::V1_1)
+            .json_body(&Item { id: 7, name: "widget" })
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(req.body, Some(Body::from(r#"{"id":7,"name":"widget"}"#)));
+        assert_eq!(req.get_header("Content-Type"), Some("application/json"));
+        assert_eq!(req.get_header("Content-Length"), Some("24"));
+
+        let res = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Created)
+            .json_body(&[Item { id: 1, name: "a" }])
+            .unwrap()
+            .try_build()
+            .unwrap();
+        assert_eq!(res.body, Some(Body::from(r#"[{"id":1,"name":"a"}]"#)));
+        assert_eq!(res.get_header_all("Content-Length"), vec!["21"]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_body_serialization_failure() {
+        let map: std::collections::HashMap<(u8, u8), u8> = [((1, 2), 3)].into_iter().collect();
+        let res = RequestPacketBuilder::new().json_body(&map);
+        assert!(matches!(res, Err(PacketErr::JsonSerializationFailed(_))));
+    }
+
+    #[test]
+    fn request_round_trip() {
+        let rp = RequestPacket::try_from_str("POST /submit HTTP/1.1\r\nHost: example.com\r\nContent-Length: 5\r\n\r\nhello").unwrap();
+        let json: String = serde_json::to_string(&rp).unwrap();
+        assert_eq!(
+            json,
+            r#"{"method":"Post","url":"/submit","version":"HTTP/1.1","headers":[{"key":"Host","value":"example.com"},{"key":"Content-Length","value":"5"}],"body":"hello"}"#
+        );
+        assert_eq!(serde_json::from_str::<RequestPacket>(&json).unwrap(), rp);
+    }
+
+    #[test]
+    fn response_round_trip() {
+        let packet = ResponsePacket::try_from_str("HTTP/1.0 404 Not Found\r\nContent-Length: 9\r\n\r\nnot found").unwrap();
+        let json: String = serde_json::to_string(&packet).unwrap();
+        assert!(json.contains(r#""version":"HTTP/1.0","status":404"#));
+        assert_eq!(serde_json::from_str::<ResponsePacket>(&json).unwrap(), packet);
+    }
+
+    #[test]
+    fn custom_status_round_trip() {
+        let packet = ResponsePacket::try_from_str("HTTP/1.1 599 Network Timeout\r\n\r\n").unwrap();
+        let json: String = serde_json::to_string(&packet).unwrap();
+        assert!(json.contains(r#""status":{"code":599,"reason":"Network Timeout"}"#));
+        assert_eq!(serde_json::from_str::<ResponsePacket>(&json).unwrap(), packet);
+
+        // a bare custom code has an empty reason phrase
+        assert_eq!(serde_json::from_str::<StatusCode>("599").unwrap(), StatusCode::Custom(599, String::new()));
+    }
+
+    #[test]
+    fn binary_body_and_http_0_9() {
+        let packet = ResponsePacket {
+            version: Version::V0_9,
+            status: None,
+            reason: None,
+            headers: None,
+            body: Some(Body::Bytes(vec![0xff, 0x00])),
+        };
+        let json: String = serde_json::to_string(&packet).unwrap();
+        assert_eq!(json, r#"{"version":"HTTP/0.9","status":null,"headers":null,"body":[255,0]}"#);
+        assert_eq!(serde_json::from_str::<ResponsePacket>(&json).unwrap(), packet);
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(serde_json::from_str::<StatusCode>("999").is_err());
+        assert!(serde_json::from_str::<StatusCode>(r#"{"code":999,"reason":"Nope"}"#).is_err());
+        assert!(serde_json::from_str::<Version>(r#""HTTP/3""#).is_err());
+    }
+}
@@ -1,6 +1,32 @@
 // reader.rs
 // optional feature
 
+use crate::packet::{content_length, PacketErr, RequestPacket, RequestPacketBuilder, ResponsePacket, ResponsePacketBuilder};
+use crate::{Method, StatusCode};
+
+/// An error that occurs when reading packets from a stream and parsing them
+#[derive(Debug)]
+pub enum ReaderErr {
+    /// Reading from the stream failed, or the stream ended before a full packet was read
+    Io(std::io::Error),
+    /// The bytes read could not be parsed into a packet
+    Packet(PacketErr),
+    /// The packet (start line, headers and body) is larger than the allowed total size. Includes the limit in bytes.
+    PacketTooLarge(usize),
+}
+
+impl From<std::io::Error> for ReaderErr {
+    fn from(err: std::io::Error) -> Self {
+        ReaderErr::Io(err)
+    }
+}
+
+impl From<PacketErr> for ReaderErr {
+    fn from(err: PacketErr) -> Self {
+        ReaderErr::Packet(err)
+    }
+}
+
 /// Read from buffer until `\r\n`. `\r\n` is included at the end if found, and excluded if buffer end reached.
 pub fn read_until_crlf<R: std::io::Read>(reader: &mut R) -> std::io::Result<Vec<u8>> {
     // Buffer to store the bytes read from the input
@@ -150,12 +176,155 @@ mod double_crlf_tests {
 }
 
 
+/// Read a body with `Transfer-Encoding: chunked` framing and return the decoded bytes.
+///
+/// Reads chunk-size lines (hex, optionally followed by `;` extensions) and the chunk data after each,
+/// until the zero-size chunk and the optional trailer fields ending with an empty line.
+/// Fails with `ErrorKind::InvalidData` on a malformed chunk, and `ErrorKind::UnexpectedEof` if the stream ends early.
+///
+/// The decoded body is limited to [`DEFAULT_MAX_CHUNKED_BODY`] bytes; use [`read_chunked_body_limited`] for other limits.
+pub fn read_chunked_body<R: std::io::Read>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+    read_chunked_body_limited(reader, DEFAULT_MAX_CHUNKED_BODY, DEFAULT_MAX_CHUNKED_BODY)
+}
+
+/// Largest decoded chunked body accepted by [`read_chunked_body`] and [`read_full_packet`] (64 MiB)
+pub const DEFAULT_MAX_CHUNKED_BODY: usize = 64 * 1024 * 1024;
+
+/// Same as [`read_chunked_body`], but fails with `ErrorKind::InvalidData` on a chunk larger than `max_chunk_size`,
+/// or once the decoded body would exceed `max_total_body` bytes.
+///
+/// The sizes are checked as soon as a chunk-size line is read, so a peer announcing e.g. an `FFFFFFFF`-byte chunk
+/// is rejected right away. Within the limits, the buffer only grows as chunk data arrives.
+pub fn read_chunked_body_limited<R: std::io::Read>(reader: &mut R, max_chunk_size: usize, max_total_body: usize) -> std::io::Result<Vec<u8>> {
+    use std::io::{Error, ErrorKind, Read};
+
+    let mut body: Vec<u8> = Vec::new();
+
+    loop {
+        let size = read_chunk_size(reader)?;
+        if size == 0 {
+            skip_trailers(reader)?;
+            return Ok(body);
+        }
+
+        if size > max_chunk_size {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                ChunkedBodyTooLarge(format!("Chunk of {size} bytes exceeds the limit of {max_chunk_size} bytes")),
+            ));
+        }
+        if body.len().checked_add(size).is_none_or(|total| total > max_total_body) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                ChunkedBodyTooLarge(format!("Chunked body exceeds the limit of {max_total_body} bytes")),
+            ));
+        }
+
+        let received = reader.take(size as u64).read_to_end(&mut body)?;
+        if received < size {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("Expected {size} bytes of chunk data, but only received {received}"),
+            ));
+        }
+        read_chunk_end(reader)?;
+    }
+}
+
+/// Error payload for a chunked body over its limits, so that the limited readers can tell it apart from a malformed body
+#[derive(Debug)]
+struct ChunkedBodyTooLarge(String);
+
+impl std::fmt::Display for ChunkedBodyTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ChunkedBodyTooLarge {}
+
+/// Read one line of a chunked body, without the `\r\n` at the end
+fn read_chunk_line<R: std::io::Read>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+    let mut line = read_until_crlf(reader)?;
+    if !line.ends_with(b"\r\n") {
+        return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "Ran out of bytes inside a chunked body"));
+    }
+    line.truncate(line.len() - 2);
+    Ok(line)
+}
+
+/// Read a chunk-size line and return the size, ignoring chunk extensions
+fn read_chunk_size<R: std::io::Read>(reader: &mut R) -> std::io::Result<usize> {
+    let size_line = read_chunk_line(reader)?;
+    let size_str = String::from_utf8_lossy(&size_line);
+    let size_str = size_str.split(';').next().unwrap_or("").trim();
+    usize::from_str_radix(size_str, 16).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid chunk size: {size_str:?}"))
+    })
+}
+
+/// Read the `\r\n` that follows the data of a chunk
+fn read_chunk_end<R: std::io::Read>(reader: &mut R) -> std::io::Result<()> {
+    let mut crlf = [0u8; 2];
+    reader.read_exact(&mut crlf)?;
+    if &crlf != b"\r\n" {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Chunk data is not followed by \\r\\n"));
+    }
+    Ok(())
+}
+
+/// Skip the trailer fields after the last chunk, up to and including the empty line
+fn skip_trailers<R: std::io::Read>(reader: &mut R) -> std::io::Result<()> {
+    while !read_chunk_line(reader)?.is_empty() {}
+    Ok(())
+}
+
 /// Valid for both response and request packets.
 /// **NOTE**: Not implemented for HTTP/0.9 (because its response headers have no `\r\n` sequences.
 ///
 /// Fails if not enough bytes read to guarantee a proper packet with the specified length.
+///
+/// If the final `Transfer-Encoding` is `chunked`, the body is read with [`read_chunked_body`] and returned decoded,
+/// and `Content-Length` is ignored.
 pub fn read_full_packet<R: std::io::Read>(reader: &mut R) -> std::io::Result<(String, Option<String>)> {
+    read_full_packet_limited(reader, usize::MAX)
+}
+
+/// Same as [`read_full_packet`], but fails with `ErrorKind::InvalidData` once the start line and headers
+/// exceed `max_header_bytes` (including the terminating `\r\n\r\n`).
+///
+/// Use this on untrusted streams, so that a peer that never ends its headers can't make the buffer grow without bound.
+pub fn read_full_packet_limited<R: std::io::Read>(reader: &mut R, max_header_bytes: usize) -> std::io::Result<(String, Option<String>)> {
+    let headers_str = read_head(reader, max_header_bytes)?;
+    let body = read_body(reader, &headers_str, DEFAULT_MAX_CHUNKED_BODY)?;
+    Ok((headers_str, body))
+}
+
+/// Same as [`read_full_packet`], but fails with `ErrorKind::InvalidData` if both `Content-Length` and `Transfer-Encoding` are present.
+///
+/// A message with both is ambiguous: RFC 7230 3.3.3 lets the receiver either reject it or let `Transfer-Encoding` win.
+/// If a proxy and the server behind it pick different framings, a request body can be made to hide a second request
+/// (request smuggling), so rejecting the message outright is the safe choice. The body is not read in that case.
+pub fn read_full_packet_strict<R: std::io::Read>(reader: &mut R) -> std::io::Result<(String, Option<String>)> {
+    let headers_str = read_head(reader, usize::MAX)?;
+
+    let has_header = |name: &str| headers_str
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .any(|(key, _)| key.trim().eq_ignore_ascii_case(name));
+    if has_header("Content-Length") && has_header("Transfer-Encoding") {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Both Content-Length and Transfer-Encoding are present",
+        ));
+    }
 
+    let body = read_body(reader, &headers_str, DEFAULT_MAX_CHUNKED_BODY)?;
+    Ok((headers_str, body))
+}
+
+/// Read the start line and headers, up to and including the `\r\n\r\n` ending them
+fn read_head<R: std::io::Read>(reader: &mut R, max_header_bytes: usize) -> std::io::Result<String> {
     use std::io::{Error, ErrorKind};
 
     let mut header_buffer = Vec::new();
@@ -165,6 +334,13 @@ pub fn read_full_packet<R: std::io::Read>(reader: &mut R) -> std::io::Result<(St
     while reader.read(&mut temp)? == 1 {
         header_buffer.push(temp[0]);
 
+        if header_buffer.len() > max_header_bytes {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Headers exceed the limit of {max_header_bytes} bytes"),
+            ));
+        }
+
         if header_buffer.len() >= 4 && &header_buffer[header_buffer.len() - 4..] == b"\r\n\r\n" {
             break;
         }
@@ -179,13 +355,36 @@ pub fn read_full_packet<R: std::io::Read>(reader: &mut R) -> std::io::Result<(St
     }
 
     // Convert header buffer to string for parsing
-    let headers_str = String::from_utf8(header_buffer.clone())
-        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    String::from_utf8(header_buffer)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Read the body framed by the headers read with [`read_head`]. `None` if there is neither `Content-Length` nor chunked `Transfer-Encoding`.
+///
+/// A chunked body is decoded and limited to `max_chunked_body` bytes.
+fn read_body<R: std::io::Read>(reader: &mut R, headers_str: &str, max_chunked_body: usize) -> std::io::Result<Option<String>> {
+    use std::io::{Error, ErrorKind, Read};
 
-    // Look for Content-Length header
-    let body = if let Some(content_length_line) = headers_str
+    // A chunked body takes precedence over Content-Length
+    let chunked: bool = headers_str
         .lines()
-        .find(|line| line.to_ascii_lowercase().starts_with("content-length:"))
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(key, _)| key.trim().eq_ignore_ascii_case("transfer-encoding"))
+        .flat_map(|(_, value)| value.split(','))
+        .map(|coding| coding.trim())
+        .rfind(|coding| !coding.is_empty())
+        .is_some_and(|coding| coding.eq_ignore_ascii_case("chunked"));
+    if chunked {
+        let body = read_chunked_body_limited(reader, max_chunked_body, max_chunked_body)?;
+        let body = String::from_utf8(body).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        return Ok(Some(body));
+    }
+
+    // Look for Content-Length headers
+    let mut declared_length: Option<usize> = None;
+    for content_length_line in headers_str
+        .lines()
+        .filter(|line| line.to_ascii_lowercase().starts_with("content-length:"))
     {
         // Extract the numeric part
         let parts: Vec<&str> = content_length_line.splitn(2, ':').collect();
@@ -194,26 +393,34 @@ pub fn read_full_packet<R: std::io::Read>(reader: &mut R) -> std::io::Result<(St
             .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Malformed Content-Length header"))?;
 
         // Parse the size
-        let content_length: usize = size_str.parse().map_err(|_| {
+        let size: usize = size_str.parse().map_err(|_| {
             Error::new(ErrorKind::InvalidData, "Invalid Content-Length value")
         })?;
 
+        // Repeated Content-Length headers are only acceptable if they all agree
+        if declared_length.is_some_and(|len| len != size) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Conflicting Content-Length values",
+            ));
+        }
+        declared_length = Some(size);
+    }
+
+    let body = if let Some(content_length) = declared_length {
         // Read the body
-        let mut body_buffer = vec![0u8; content_length];
-        let mut total_read = 0;
-
-        while total_read < content_length {
-            let bytes_read = reader.read(&mut body_buffer[total_read..])?;
-            if bytes_read == 0 {
-                return Err(Error::new(
-                    ErrorKind::UnexpectedEof,
-                    format!(
-                        "Expected {} bytes for body, but only received {}",
-                        content_length, total_read
-                    ),
-                ));
-            }
-            total_read += bytes_read;
+        // The buffer grows as bytes arrive, so a huge declared length alone doesn't allocate anything
+        let mut body_buffer = Vec::new();
+        let total_read = reader.take(content_length as u64).read_to_end(&mut body_buffer)?;
+
+        if total_read < content_length {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "Expected {} bytes for body, but only received {}",
+                    content_length, total_read
+                ),
+            ));
         }
 
         Some(String::from_utf8(body_buffer)
@@ -222,7 +429,318 @@ pub fn read_full_packet<R: std::io::Read>(reader: &mut R) -> std::io::Result<(St
         None
     };
 
-    Ok((headers_str, body))
+    Ok(body)
+}
+
+/// Same as [`read_body`], but a chunked body is returned with its chunk framing, exactly as it was read.
+///
+/// A packet parsed from the head and this body keeps its `Transfer-Encoding: chunked`, so it has to carry the framing
+/// to pass `validate()` and to serialize back to valid wire bytes.
+fn read_framed_body<R: std::io::Read>(reader: &mut R, headers_str: &str, max_chunked_body: usize) -> std::io::Result<Option<String>> {
+    let mut raw: Vec<u8> = Vec::new();
+    let mut capturing = CapturingReader { inner: reader, sink: &mut raw };
+    if read_body(&mut capturing, headers_str, max_chunked_body)?.is_none() {
+        return Ok(None);
+    }
+    let body = String::from_utf8(raw).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(Some(body))
+}
+
+/// Read a single request (headers and body) from the reader and parse it.
+///
+/// The body is read according to its `Content-Length` or chunked `Transfer-Encoding`, so reading stops right
+/// at the end of the request. This allows calling it in a loop directly on a `TcpStream`.
+/// A chunked body is kept with its chunk framing, so the request serializes back to the bytes read.
+pub fn read_request<R: std::io::Read>(reader: &mut R) -> Result<RequestPacket, ReaderErr> {
+    let head = read_head(reader, usize::MAX)?;
+    let body = read_framed_body(reader, &head, DEFAULT_MAX_CHUNKED_BODY)?;
+    request_from_parts(head, body)
+}
+
+/// Parse a request from its head and body, as read by [`read_framed_body`]
+fn request_from_parts(head: String, body: Option<String>) -> Result<RequestPacket, ReaderErr> {
+    let raw: String = head + body.as_deref().unwrap_or("");
+    let packet = RequestPacket::try_from_str(&raw)?;
+    Ok(packet)
+}
+
+/// Read a single response (status line, headers and body) from the reader and parse it.
+///
+/// The body is read according to its `Content-Length` or chunked `Transfer-Encoding`, and a chunked body keeps its framing.
+/// A response with neither is read without a body, since its end would only be marked by the connection closing.
+/// HTTP/0.9 responses are not supported.
+pub fn read_response<R: std::io::Read>(reader: &mut R) -> Result<ResponsePacket, ReaderErr> {
+    let head = read_head(reader, usize::MAX)?;
+    let body = read_framed_body(reader, &head, DEFAULT_MAX_CHUNKED_BODY)?;
+    response_from_parts(head, body)
+}
+
+/// Parse a response from its head and body, as read by [`read_framed_body`]
+fn response_from_parts(head: String, body: Option<String>) -> Result<ResponsePacket, ReaderErr> {
+    let raw: String = head + body.as_deref().unwrap_or("");
+    let packet = ResponsePacketBuilder::try_from_str(&raw)?.try_build()?;
+    Ok(packet)
+}
+
+/// Same as [`read_response`], but aware of the method of the request being answered.
+///
+/// Responses to `HEAD` requests, as well as `1xx`, `204 No Content` and `304 Not Modified` responses, never have a body,
+/// even if they carry a `Content-Length`. For these, no body is read, so the call doesn't block waiting for bytes that never come.
+pub fn read_response_for_method<R: std::io::Read>(reader: &mut R, request_method: Method) -> Result<ResponsePacket, ReaderErr> {
+    let head: String = read_head(reader, usize::MAX)?;
+    let status: StatusCode = StatusCode::try_from_first_res_line(head.split("\r\n").next().unwrap_or(""))?;
+
+    let body: Option<String> = if request_method == Method::Head || !status.allows_body() {
+        None
+    } else {
+        read_framed_body(reader, &head, DEFAULT_MAX_CHUNKED_BODY)?
+    };
+    response_from_parts(head, body)
+}
+
+/// Reads requests one after another from a keep-alive connection, e.g. a `TcpStream`.
+///
+/// The stream is read in blocks into an internal buffer, and bytes belonging to the next request are kept for the next call.
+/// Also usable as an iterator over the requests.
+pub struct PacketReader<R: std::io::Read> {
+    inner: std::io::BufReader<R>,
+}
+
+impl<R: std::io::Read> PacketReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { inner: std::io::BufReader::new(reader) }
+    }
+
+    /// Read and parse the next request.
+    ///
+    /// `None` if the stream ended cleanly between two requests. A stream ending in the middle of a request is an error.
+    pub fn next_request(&mut self) -> Option<Result<RequestPacket, ReaderErr>> {
+        use std::io::BufRead;
+
+        match self.inner.fill_buf() {
+            Ok([]) => None,
+            Ok(_) => Some(read_request(&mut self.inner)),
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+
+    /// The underlying reader. Bytes that were buffered but not consumed yet are lost.
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+impl<R: std::io::Read> Iterator for PacketReader<R> {
+    type Item = Result<RequestPacket, ReaderErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_request()
+    }
+}
+
+/// Read the start line and headers of a request, and return the request without its body,
+/// together with a [`BodyReader`] that streams the body from `reader`.
+///
+/// Use this for bodies that are too large to buffer. The body must be read to the end before reading the next request from `reader`.
+pub fn read_request_streaming<R: std::io::Read>(reader: &mut R) -> Result<(RequestPacket, BodyReader<'_, R>), ReaderErr> {
+    let head: String = read_head(reader, usize::MAX)?;
+    let packet: RequestPacket = RequestPacketBuilder::try_from_head(&head)?.try_build()?;
+
+    let framing: BodyFraming = if packet.transfer_encodings().last().is_some_and(|coding| coding == "chunked") {
+        BodyFraming::Chunked { remaining: 0, started: false }
+    } else {
+        BodyFraming::Length(content_length(&packet.headers)?.unwrap_or(0))
+    };
+    Ok((packet, BodyReader { inner: reader, framing }))
+}
+
+/// How the body streamed by a [`BodyReader`] is delimited, and how much of it is left
+enum BodyFraming {
+    /// `Content-Length`, with the number of bytes left
+    Length(usize),
+    /// Chunked `Transfer-Encoding`, with the number of bytes left in the current chunk
+    Chunked { remaining: usize, started: bool },
+    /// The last chunk and the trailers have been read
+    Done,
+}
+
+/// Streams the body of a request read with [`read_request_streaming`].
+///
+/// Yields exactly the `Content-Length` bytes, or the decoded data of a chunked body, and then EOF.
+/// Nothing past the end of the body is consumed from the underlying reader.
+/// Fails with `ErrorKind::UnexpectedEof` if the stream ends before the body does.
+pub struct BodyReader<'r, R: std::io::Read> {
+    inner: &'r mut R,
+    framing: BodyFraming,
+}
+
+impl<R: std::io::Read> std::io::Read for BodyReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let remaining: &mut usize = match &mut self.framing {
+            BodyFraming::Done | BodyFraming::Length(0) => return Ok(0),
+            BodyFraming::Length(remaining) => remaining,
+            BodyFraming::Chunked { remaining, started } => {
+                if *remaining == 0 {
+                    if *started {
+                        read_chunk_end(self.inner)?;
+                    }
+                    *started = true;
+                    *remaining = read_chunk_size(self.inner)?;
+                    if *remaining == 0 {
+                        skip_trailers(self.inner)?;
+                        self.framing = BodyFraming::Done;
+                        return Ok(0);
+                    }
+                }
+                remaining
+            }
+        };
+
+        let len = buf.len().min(*remaining);
+        let n = self.inner.read(&mut buf[..len])?;
+        if n == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "Ran out of bytes inside the body"));
+        }
+        *remaining -= n;
+        Ok(n)
+    }
+}
+
+/// Wraps a reader and fails once more than `remaining` bytes are requested from it
+struct LimitedReader<'a, R: std::io::Read> {
+    inner: &'a mut R,
+    remaining: usize,
+    exceeded: bool,
+}
+
+impl<R: std::io::Read> std::io::Read for LimitedReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.remaining == 0 {
+            self.exceeded = true;
+            return Err(std::io::Error::other("Packet size limit exceeded"));
+        }
+        let len = buf.len().min(self.remaining);
+        let n = self.inner.read(&mut buf[..len])?;
+        self.remaining -= n;
+        Ok(n)
+    }
+}
+
+/// Same as [`read_request`], but fails with [`ReaderErr::PacketTooLarge`] once the whole request (headers and body)
+/// exceeds `max_total_bytes`. No more than `max_total_bytes` bytes are consumed from the reader.
+pub fn read_request_limited<R: std::io::Read>(reader: &mut R, max_total_bytes: usize) -> Result<RequestPacket, ReaderErr> {
+    let (head, body) = read_full_packet_within(reader, max_total_bytes)?;
+    request_from_parts(head, body)
+}
+
+/// Same as [`read_response`], but fails with [`ReaderErr::PacketTooLarge`] once the whole response (headers and body)
+/// exceeds `max_total_bytes`. No more than `max_total_bytes` bytes are consumed from the reader.
+pub fn read_response_limited<R: std::io::Read>(reader: &mut R, max_total_bytes: usize) -> Result<ResponsePacket, ReaderErr> {
+    let (head, body) = read_full_packet_within(reader, max_total_bytes)?;
+    response_from_parts(head, body)
+}
+
+/// Read the head and body of a packet through a [`LimitedReader`], failing with [`ReaderErr::PacketTooLarge`]
+/// once the packet exceeds `max_total_bytes`.
+///
+/// A chunked body is limited to the bytes left after the head, since its decoded size can't be larger than that.
+fn read_full_packet_within<R: std::io::Read>(reader: &mut R, max_total_bytes: usize) -> Result<(String, Option<String>), ReaderErr> {
+    let mut limited = LimitedReader { inner: reader, remaining: max_total_bytes, exceeded: false };
+    let res = read_head(&mut limited, usize::MAX).and_then(|head| {
+        let budget = limited.remaining;
+        let body = read_framed_body(&mut limited, &head, budget)?;
+        Ok((head, body))
+    });
+    match res {
+        _ if limited.exceeded => Err(ReaderErr::PacketTooLarge(max_total_bytes)),
+        Err(e) if e.get_ref().is_some_and(|inner| inner.is::<ChunkedBodyTooLarge>()) => {
+            Err(ReaderErr::PacketTooLarge(max_total_bytes))
+        }
+        res => Ok(res?),
+    }
+}
+
+/// Read exactly `n` pipelined requests from the reader.
+///
+/// Fails if the stream ends before `n` full requests were read, or if any of them can't be parsed.
+pub fn read_n_requests<R: std::io::Read>(reader: &mut R, n: usize) -> Result<Vec<RequestPacket>, ReaderErr> {
+    let mut requests: Vec<RequestPacket> = Vec::with_capacity(n);
+    for _ in 0..n {
+        requests.push(read_request(reader)?);
+    }
+    Ok(requests)
+}
+
+/// Wraps a reader and appends every byte read from it to `sink`
+struct CapturingReader<'a, R: std::io::Read> {
+    inner: &'a mut R,
+    sink: &'a mut Vec<u8>,
+}
+
+impl<R: std::io::Read> std::io::Read for CapturingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.sink.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Same as [`read_full_packet`], but every byte consumed from the reader is also appended to `sink`.
+///
+/// Useful for logging or replaying the exact wire bytes. The bytes are captured even if reading fails midway.
+pub fn read_full_packet_capturing<R: std::io::Read>(reader: &mut R, sink: &mut Vec<u8>) -> std::io::Result<(String, Option<String>)> {
+    let mut capturing = CapturingReader { inner: reader, sink };
+    read_full_packet(&mut capturing)
+}
+
+/// Wraps a reader and fails every read once `deadline` has passed.
+///
+/// `set_timeout` is called with the remaining time before each read, so that a blocking read can't outlive the deadline.
+struct DeadlineReader<R: std::io::Read> {
+    inner: R,
+    deadline: std::time::Instant,
+    set_timeout: fn(&R, std::time::Duration) -> std::io::Result<()>,
+}
+
+impl<R: std::io::Read> std::io::Read for DeadlineReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        use std::io::{Error, ErrorKind};
+
+        let deadline_passed = || Error::new(ErrorKind::TimedOut, "Deadline passed before the full packet was read");
+
+        let remaining = self
+            .deadline
+            .checked_duration_since(std::time::Instant::now())
+            .filter(|d| !d.is_zero())
+            .ok_or_else(deadline_passed)?;
+        (self.set_timeout)(&self.inner, remaining)?;
+
+        match self.inner.read(buf) {
+            // a read timeout shows up as `WouldBlock` on Unix and `TimedOut` on Windows
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Err(deadline_passed()),
+            res => res,
+        }
+    }
+}
+
+/// Same as [`read_full_packet`], but fails with `ErrorKind::TimedOut` once `deadline` passes, no matter how the bytes trickle in.
+///
+/// A plain read timeout only bounds each single read, so a peer sending one byte at a time (slowloris) could keep the connection
+/// busy forever. The read timeout of `stream` is overwritten and left set when this returns.
+pub fn read_full_packet_deadline(stream: &std::net::TcpStream, deadline: std::time::Instant) -> std::io::Result<(String, Option<String>)> {
+    let mut reader = DeadlineReader {
+        inner: stream,
+        deadline,
+        set_timeout: |stream, remaining| stream.set_read_timeout(Some(remaining)),
+    };
+    read_full_packet(&mut reader)
 }
 
 #[cfg(test)]
@@ -284,6 +802,40 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn returns_error_on_conflicting_content_length() {
+        let conflicting = b"HTTP/1.0 200 OK\r\nContent-Length: 5\r\nContent-Length: 3\r\n\r\nHello";
+        let mut cursor = Cursor::new(conflicting);
+
+        let result = read_full_packet(&mut cursor);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn accepts_repeated_matching_content_length() {
+        let repeated = b"HTTP/1.0 200 OK\r\nContent-Length: 5\r\ncontent-length: 5\r\n\r\nHello";
+        let mut cursor = Cursor::new(repeated);
+
+        let (_, body) = read_full_packet(&mut cursor).unwrap();
+        assert_eq!(body, Some("Hello".to_string()));
+    }
+
+    #[test]
+    fn strict_rejects_content_length_with_transfer_encoding() {
+        let input = b"POST / HTTP/1.1\r\nContent-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n";
+        let err = read_full_packet_strict(&mut Cursor::new(&input[..])).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // the lenient reader lets the chunked encoding win
+        let (_, body) = read_full_packet(&mut Cursor::new(&input[..])).unwrap();
+        assert_eq!(body, Some(String::new()));
+
+        let input = b"POST / HTTP/1.1\r\nContent-Length: 4\r\n\r\nbody";
+        let (_, body) = read_full_packet_strict(&mut Cursor::new(&input[..])).unwrap();
+        assert_eq!(body, Some("body".to_string()));
+    }
+
     #[test]
     fn returns_error_on_invalid_content_length() {
         let bad_header = b"HTTP/1.0 200 OK\r\nContent-Length: notanumber\r\n\r\n";
@@ -295,3 +847,505 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod limited_tests {
+    use super::*;
+    use std::io::{Cursor, ErrorKind, Read};
+
+    #[test]
+    fn within_limit() {
+        let input = b"HTTP/1.0 200 OK\r\nContent-Length: 5\r\n\r\nHello";
+        let mut cursor = Cursor::new(input);
+
+        // the limit covers the headers only, not the body
+        let (headers, body) = read_full_packet_limited(&mut cursor, input.len() - 5).unwrap();
+        assert_eq!(headers, "HTTP/1.0 200 OK\r\nContent-Length: 5\r\n\r\n");
+        assert_eq!(body, Some("Hello".to_string()));
+    }
+
+    #[test]
+    fn errors_past_limit() {
+        // a peer that never ends its headers
+        let mut endless = std::io::repeat(b'a');
+        let err = read_full_packet_limited(&mut endless, 1024).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let input = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let mut cursor = Cursor::new(input);
+        let err = read_full_packet_limited(&mut cursor, 20).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        // reading stopped right past the limit
+        assert_eq!(cursor.bytes().count(), input.len() - 21);
+    }
+}
+
+#[cfg(test)]
+mod deadline_tests {
+    use super::*;
+    use std::io::{Cursor, ErrorKind, Read};
+    use std::time::{Duration, Instant};
+
+    /// Returns one byte per read, sleeping before each
+    struct Throttled<'a> {
+        inner: Cursor<&'a [u8]>,
+        delay: Duration,
+    }
+
+    impl Read for Throttled<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            std::thread::sleep(self.delay);
+            let len = buf.len().min(1);
+            self.inner.read(&mut buf[..len])
+        }
+    }
+
+    fn throttled_deadline_reader(input: &[u8], delay: Duration, deadline: Instant) -> DeadlineReader<Throttled<'_>> {
+        DeadlineReader {
+            inner: Throttled { inner: Cursor::new(input), delay },
+            deadline,
+            set_timeout: |_, _| Ok(()),
+        }
+    }
+
+    #[test]
+    fn trickling_peer_hits_deadline() {
+        let input = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let deadline = Instant::now() + Duration::from_millis(50);
+        let mut reader = throttled_deadline_reader(input, Duration::from_millis(10), deadline);
+
+        let err = read_full_packet(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn completes_before_deadline() {
+        let input = b"GET / HTTP/1.1\r\n\r\n";
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut reader = throttled_deadline_reader(input, Duration::ZERO, deadline);
+
+        let (headers, body) = read_full_packet(&mut reader).unwrap();
+        assert_eq!(headers, "GET / HTTP/1.1\r\n\r\n");
+        assert_eq!(body, None);
+    }
+}
+
+#[cfg(test)]
+mod chunked_tests {
+    use super::*;
+    use std::io::{Cursor, ErrorKind};
+
+    #[test]
+    fn two_chunks() {
+        let input = b"5\r\nHello\r\n8;ext=1\r\n, world!\r\n0\r\n\r\nnext";
+        let mut cursor = Cursor::new(input);
+
+        assert_eq!(read_chunked_body(&mut cursor).unwrap(), b"Hello, world!");
+        // the bytes after the terminator are left unread
+        assert_eq!(cursor.position(), input.len() as u64 - 4);
+    }
+
+    #[test]
+    fn trailers() {
+        let input = b"3\r\nabc\r\n0\r\nExpires: never\r\n\r\n";
+        let mut cursor = Cursor::new(input);
+
+        assert_eq!(read_chunked_body(&mut cursor).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn malformed_chunk_size() {
+        let mut cursor = Cursor::new(b"zz\r\nabc\r\n0\r\n\r\n");
+        assert_eq!(read_chunked_body(&mut cursor).unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let mut cursor = Cursor::new(b"3\r\nabcd\r\n0\r\n\r\n");
+        assert_eq!(read_chunked_body(&mut cursor).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut cursor = Cursor::new(b"5\r\nHel");
+        assert_eq!(read_chunked_body(&mut cursor).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn enormous_chunk_size_rejected() {
+        let mut reader = Cursor::new(&b"FFFFFFFF\r\nabc"[..]);
+        let err = read_chunked_body_limited(&mut reader, 1024, 1024 * 1024).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let mut reader = Cursor::new(&b"FFFFFFFFFFFFFFFF\r\nabc"[..]);
+        let err = read_chunked_body_limited(&mut reader, usize::MAX, 1024).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn total_body_limit() {
+        let input = b"4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
+        let err = read_chunked_body_limited(&mut Cursor::new(&input[..]), 8, 8).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let body = read_chunked_body_limited(&mut Cursor::new(&input[..]), 5, 9).unwrap();
+        assert_eq!(body, b"Wikipedia");
+    }
+
+    #[test]
+    fn full_packet_two_chunks() {
+        let input = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nHello\r\n8\r\n, world!\r\n0\r\n\r\n";
+        let mut cursor = Cursor::new(input);
+
+        let (headers, body) = read_full_packet(&mut cursor).unwrap();
+        assert_eq!(headers, "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n");
+        assert_eq!(body, Some("Hello, world!".to_string()));
+    }
+
+    #[test]
+    fn full_packet_huge_chunk_size() {
+        for size in ["FFFFFFFFFFFFFFFF", "7FFFFFFFFFFF"] {
+            let input = format!("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{size}\r\nabc");
+            let err = read_full_packet(&mut Cursor::new(input)).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+
+        // without limits, the chunk is read as it arrives instead of being allocated up front
+        let mut reader = Cursor::new(&b"7FFFFFFFFFFF\r\nabc"[..]);
+        let err = read_chunked_body_limited(&mut reader, usize::MAX, usize::MAX).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
+
+#[cfg(test)]
+mod capturing_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn sink_equals_consumed_input() {
+        let input = b"HTTP/1.0 200 OK\r\nContent-Length: 5\r\n\r\nHello";
+        let mut cursor = Cursor::new(input);
+        let mut sink = Vec::new();
+
+        let (headers, body) = read_full_packet_capturing(&mut cursor, &mut sink).unwrap();
+        assert_eq!(headers, "HTTP/1.0 200 OK\r\nContent-Length: 5\r\n\r\n");
+        assert_eq!(body, Some("Hello".to_string()));
+        assert_eq!(sink, input);
+    }
+
+    #[test]
+    fn leaves_trailing_bytes_uncaptured() {
+        let input = b"GET / HTTP/1.1\r\n\r\nGET /next HTTP/1.1\r\n\r\n";
+        let mut cursor = Cursor::new(input);
+        let mut sink = Vec::new();
+
+        read_full_packet_capturing(&mut cursor, &mut sink).unwrap();
+        assert_eq!(sink, b"GET / HTTP/1.1\r\n\r\n");
+        assert_eq!(sink.len() as u64, cursor.position());
+    }
+}
+
+#[cfg(test)]
+mod read_request_tests {
+    use super::*;
+    use crate::{Body, Method, Version};
+    use std::io::Cursor;
+
+    #[test]
+    fn request_with_body() {
+        let input = b"POST /submit HTTP/1.1\r\nHost: example.com\r\nContent-Length: 11\r\n\r\nhello world";
+        let mut cursor = Cursor::new(input);
+
+        let request = read_request(&mut cursor).unwrap();
+        assert_eq!(request.method, Method::Post);
+        assert_eq!(request.url, "/submit");
+        assert_eq!(request.version, Version::V1_1);
+        assert_eq!(request.get_header("host"), Some("example.com"));
+        assert_eq!(request.body, Some(Body::Text("hello world".to_string())));
+        assert_eq!(cursor.position() as usize, input.len());
+    }
+
+    #[test]
+    fn chunked_request_keeps_framing() {
+        let input = "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+        let request = read_request(&mut Cursor::new(input)).unwrap();
+
+        assert_eq!(request.body, Some(Body::Text("5\r\nhello\r\n0\r\n\r\n".to_string())));
+        assert_eq!(request.validate(), Ok(()));
+        assert_eq!(request.to_string(), input);
+        assert_eq!(read_request(&mut Cursor::new(request.to_string())).unwrap(), request);
+    }
+
+    #[test]
+    fn unparsable_request() {
+        let mut cursor = Cursor::new(b"NOT A REQUEST LINE\r\n\r\n");
+        match read_request(&mut cursor) {
+            Err(ReaderErr::Packet(_)) => {}
+            other => panic!("expected a packet error, got {other:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod read_response_tests {
+    use super::*;
+    use crate::{Body, StatusCode, Version};
+    use std::io::Cursor;
+
+    #[test]
+    fn ok_with_body() {
+        let input = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\nHello";
+        let mut cursor = Cursor::new(input);
+
+        let response = read_response(&mut cursor).unwrap();
+        assert_eq!(response.version, Version::V1_1);
+        assert_eq!(response.status, Some(StatusCode::Ok));
+        assert_eq!(response.get_header("content-type"), Some("text/plain"));
+        assert_eq!(response.body, Some(Body::Text("Hello".to_string())));
+        assert_eq!(cursor.position() as usize, input.len());
+    }
+
+    #[test]
+    fn ok_without_body() {
+        let mut cursor = Cursor::new(b"HTTP/1.1 200 OK\r\nServer: test\r\n\r\n");
+
+        let response = read_response(&mut cursor).unwrap();
+        assert_eq!(response.status, Some(StatusCode::Ok));
+        assert_eq!(response.get_header("Server"), Some("test"));
+        assert_eq!(response.body, None);
+    }
+
+    #[test]
+    fn chunked_response_keeps_framing() {
+        let input = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nHello\r\n0\r\n\r\n";
+        let response = read_response(&mut Cursor::new(input)).unwrap();
+
+        assert_eq!(response.validate(), Ok(()));
+        assert_eq!(response.try_to_string().unwrap(), input);
+    }
+}
+
+#[cfg(test)]
+mod total_limit_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn exactly_at_limit() {
+        let input = b"POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello";
+        let mut cursor = Cursor::new(input);
+
+        let request = read_request_limited(&mut cursor, input.len()).unwrap();
+        assert_eq!(request.body.unwrap().as_str(), Some("hello"));
+    }
+
+    #[test]
+    fn just_over_limit() {
+        let input = b"POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello";
+        let mut cursor = Cursor::new(input);
+
+        match read_request_limited(&mut cursor, input.len() - 1) {
+            Err(ReaderErr::PacketTooLarge(limit)) => assert_eq!(limit, input.len() - 1),
+            other => panic!("expected PacketTooLarge, got {other:?}"),
+        }
+
+        // a peer sending a body that is too large for the limit
+        let head = Cursor::new(b"HTTP/1.1 200 OK\r\nContent-Length: 1000000000000\r\n\r\n");
+        let mut endless = std::io::Read::chain(head, std::io::repeat(b'a'));
+        assert!(matches!(read_response_limited(&mut endless, 1024), Err(ReaderErr::PacketTooLarge(1024))));
+    }
+
+    #[test]
+    fn chunked_over_limit() {
+        // the announced chunk doesn't fit in what is left of the limit
+        let input = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n7FFFFFFFFFFF\r\nabc";
+        assert!(matches!(read_request_limited(&mut Cursor::new(input), 1024), Err(ReaderErr::PacketTooLarge(1024))));
+
+        let head = Cursor::new(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n");
+        let mut endless = std::io::Read::chain(head, std::io::repeat(b'a'));
+        assert!(matches!(read_response_limited(&mut endless, 1024), Err(ReaderErr::PacketTooLarge(1024))));
+
+        // small chunks adding up to more than the limit
+        let input = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n5\r\nworld\r\n0\r\n\r\n";
+        let limit = input.len() - 1;
+        assert!(matches!(read_request_limited(&mut Cursor::new(input), limit), Err(ReaderErr::PacketTooLarge(l)) if l == limit));
+        assert!(read_request_limited(&mut Cursor::new(input), input.len()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod read_response_for_method_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn head_response_with_content_length() {
+        let input = b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n";
+        let mut cursor = Cursor::new(input);
+
+        let response = read_response_for_method(&mut cursor, Method::Head).unwrap();
+        assert_eq!(response.status, Some(StatusCode::Ok));
+        assert_eq!(response.get_header("Content-Length"), Some("100"));
+        assert_eq!(response.body, None);
+        assert_eq!(cursor.position() as usize, input.len());
+    }
+
+    #[test]
+    fn no_body_statuses() {
+        let not_modified = b"HTTP/1.1 304 Not Modified\r\nContent-Length: 100\r\n\r\nHTTP/1.1 200 OK\r\n\r\n";
+        let mut cursor = Cursor::new(not_modified);
+        let response = read_response_for_method(&mut cursor, Method::Get).unwrap();
+        assert_eq!(response.status, Some(StatusCode::NotModified));
+        assert_eq!(response.body, None);
+        // the next response is left in the stream
+        assert_eq!(read_response(&mut cursor).unwrap().status, Some(StatusCode::Ok));
+
+        let no_content = b"HTTP/1.1 204 No Content\r\nContent-Length: 5\r\n\r\n";
+        let response = read_response_for_method(&mut Cursor::new(no_content), Method::Delete).unwrap();
+        assert_eq!(response.body, None);
+    }
+
+    #[test]
+    fn get_response_reads_body() {
+        let input = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nHello";
+        let response = read_response_for_method(&mut Cursor::new(input), Method::Get).unwrap();
+        assert_eq!(response.body.unwrap().as_str(), Some("Hello"));
+    }
+}
+
+#[cfg(test)]
+mod packet_reader_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn two_pipelined_requests() {
+        let input = concat!(
+            "POST /a HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc",
+            "GET /b HTTP/1.1\r\nHost: example.com\r\n\r\n",
+        );
+        let mut reader = PacketReader::new(Cursor::new(input.as_bytes()));
+
+        let first = reader.next_request().unwrap().unwrap();
+        assert_eq!(first.url, "/a");
+        assert_eq!(first.body.unwrap().as_str(), Some("abc"));
+
+        let second = reader.next_request().unwrap().unwrap();
+        assert_eq!(second.url, "/b");
+        assert_eq!(second.get_header("Host"), Some("example.com"));
+
+        assert!(reader.next_request().is_none());
+    }
+
+    #[test]
+    fn eof_inside_request() {
+        let input = "GET /a HTTP/1.1\r\n\r\nGET /b HTTP/1.1\r\n";
+        let results: Vec<Result<RequestPacket, ReaderErr>> = PacketReader::new(Cursor::new(input.as_bytes())).collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(&results[1], Err(ReaderErr::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof));
+    }
+}
+
+#[cfg(test)]
+mod read_n_requests_tests {
+    use super::*;
+    use crate::Method;
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_three_requests() {
+        let input = concat!(
+            "GET / HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            "POST /submit HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello",
+            "DELETE /items/1 HTTP/1.1\r\n\r\n",
+        );
+        let mut cursor = Cursor::new(input.as_bytes());
+
+        let requests = read_n_requests(&mut cursor, 3).unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, Method::Get);
+        assert_eq!(requests[1].method, Method::Post);
+        assert_eq!(requests[1].body.as_ref().unwrap().as_str(), Some("hello"));
+        assert_eq!(requests[2].url, "/items/1");
+        assert_eq!(cursor.position() as usize, input.len());
+    }
+
+    #[test]
+    fn errors_when_stream_ends_early() {
+        let input = concat!(
+            "GET / HTTP/1.1\r\n\r\n",
+            "GET /other HTTP/1.1\r\n\r\n",
+        );
+        let mut cursor = Cursor::new(input.as_bytes());
+
+        match read_n_requests(&mut cursor, 3) {
+            Err(ReaderErr::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof),
+            other => panic!("expected an EOF error, got {other:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod body_reader_tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+
+    #[test]
+    fn content_length_in_small_chunks() {
+        let body: Vec<u8> = (0..1_000_000).map(|i| (i % 251) as u8).collect();
+        let mut input: Vec<u8> = format!("POST /upload HTTP/1.1\r\nContent-Length: {}\r\n\r\n", body.len()).into_bytes();
+        input.extend_from_slice(&body);
+        input.extend_from_slice(b"GET /next HTTP/1.1\r\n\r\n");
+        let mut cursor = Cursor::new(input);
+
+        let (packet, mut body_reader) = read_request_streaming(&mut cursor).unwrap();
+        assert_eq!(packet.url, "/upload");
+        assert_eq!(packet.body, None);
+
+        let mut streamed: Vec<u8> = Vec::new();
+        let mut buf = [0u8; 1000];
+        loop {
+            let n = body_reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            streamed.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(streamed, body);
+        assert_eq!(body_reader.read(&mut buf).unwrap(), 0);
+
+        // the next request is left in the stream
+        assert_eq!(read_request(&mut cursor).unwrap().url, "/next");
+    }
+
+    #[test]
+    fn chunked() {
+        let input = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6;ext=1\r\n world\r\n0\r\nTrailer: x\r\n\r\nnext";
+        let mut cursor = Cursor::new(&input[..]);
+
+        let (_, mut body_reader) = read_request_streaming(&mut cursor).unwrap();
+        let mut streamed = String::new();
+        body_reader.read_to_string(&mut streamed).unwrap();
+        assert_eq!(streamed, "hello world");
+
+        let mut rest = String::new();
+        cursor.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "next");
+    }
+
+    #[test]
+    fn no_body() {
+        let mut cursor = Cursor::new(&b"GET / HTTP/1.1\r\n\r\n"[..]);
+        let (_, mut body_reader) = read_request_streaming(&mut cursor).unwrap();
+        let mut streamed: Vec<u8> = Vec::new();
+        assert_eq!(body_reader.read_to_end(&mut streamed).unwrap(), 0);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut cursor = Cursor::new(&b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nabc"[..]);
+        let (_, mut body_reader) = read_request_streaming(&mut cursor).unwrap();
+        let mut streamed: Vec<u8> = Vec::new();
+        let err = body_reader.read_to_end(&mut streamed).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(streamed, b"abc");
+    }
+}
@@ -0,0 +1,62 @@
+// writer.rs
+// optional feature, enabled together with the reader
+
+use crate::packet::{RequestPacket, ResponsePacket};
+
+/// Serialize a request and write it to `w` in a single `write_all` call
+pub fn write_request<W: std::io::Write>(w: &mut W, req: &RequestPacket) -> std::io::Result<()> {
+    w.write_all(&req.to_bytes())
+}
+
+/// Serialize a response and write it to `w` in a single `write_all` call.
+///
+/// Fails with `ErrorKind::InvalidInput` if the response can't be serialized, e.g. an HTTP/1.1 response without a status code.
+/// Nothing is written in that case.
+pub fn write_response<W: std::io::Write>(w: &mut W, res: &ResponsePacket) -> std::io::Result<()> {
+    let bytes: Vec<u8> = res
+        .try_to_bytes()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e:?}")))?;
+    w.write_all(&bytes)
+}
+
+#[cfg(test)]
+mod writer_tests {
+    use super::*;
+    use crate::packet::ResponsePacketBuilder;
+    use crate::{Body, StatusCode, Version};
+
+    #[test]
+    fn request_bytes() {
+        let input = "POST /submit HTTP/1.1\r\nHost: example.com\r\nContent-Length: 5\r\n\r\nhello";
+        let req = RequestPacket::try_from_str(input).unwrap();
+
+        let mut out: Vec<u8> = Vec::new();
+        write_request(&mut out, &req).unwrap();
+        assert_eq!(out, input.as_bytes());
+    }
+
+    #[test]
+    fn response_bytes() {
+        let mut res = ResponsePacket::try_from_str("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n").unwrap();
+        res.body = Some(Body::Bytes(vec![0xff, 0xfe]));
+
+        let mut out: Vec<u8> = Vec::new();
+        write_response(&mut out, &res).unwrap();
+        assert_eq!(out, b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n\xff\xfe");
+    }
+
+    #[test]
+    fn invalid_response() {
+        let mut res = ResponsePacketBuilder::new()
+            .version(Version::V1_1)
+            .status(StatusCode::Ok)
+            .try_build()
+            .unwrap();
+        res.status = None;
+
+        let mut out: Vec<u8> = Vec::new();
+        let err = write_response(&mut out, &res).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(out.is_empty());
+    }
+}